@@ -1,29 +1,147 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, ItemEnum};
+use syn::{parse_macro_input, Expr, Fields, ItemEnum};
 
+/// Implements `Module` for an enum wrapping one module per variant, along with a
+/// `name(&self) -> &'static str` method returning the name of the wrapped variant.
+///
+/// The implementation is generic over the sample rate, pass `sample_rate = N` to implement
+/// `Module<N>` for a fixed sample rate instead.
+///
+/// Pass `outputs = N` to also forward an `outputs(&self) -> [Signal; N]` method, every wrapped
+/// module then has to provide an `outputs` method returning `N` signals.
 #[proc_macro_attribute]
-pub fn modularize(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn modularize(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut outputs: Option<Expr> = None;
+    let mut fixed_sample_rate: Option<Expr> = None;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("outputs") {
+            outputs = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("sample_rate") {
+            fixed_sample_rate = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported modularize argument"))
+        }
+    });
+
+    parse_macro_input!(attr with attr_parser);
+
     let input = parse_macro_input!(item as ItemEnum);
+
+    // Every variant has to wrap exactly one module, report the ones that don't on their own
+    let errors: Vec<_> = input
+        .variants
+        .iter()
+        .filter(|variant| !matches!(&variant.fields, Fields::Unnamed(f) if f.unnamed.len() == 1))
+        .map(|variant| {
+            syn::Error::new_spanned(
+                variant,
+                "modularize expects every variant to wrap a single module, e.g. `Oscillator(Oscillator)`",
+            )
+            .to_compile_error()
+        })
+        .collect();
+
+    if !errors.is_empty() {
+        return quote! {
+            #input
+            #(#errors)*
+        }
+        .into();
+    }
+
     let enum_name = &input.ident;
+
+    let (generics, sample_rate) = match fixed_sample_rate {
+        Some(rate) => (quote! {}, quote! { { #rate } }),
+        None => (
+            quote! { <const SAMPLE_RATE: usize> },
+            quote! { SAMPLE_RATE },
+        ),
+    };
     let mut is_ready_arms = Vec::new();
     let mut process_arms = Vec::new();
+    let mut process_block_arms = Vec::new();
+    let mut latency_arms = Vec::new();
+    let mut cost_hint_arms = Vec::new();
+    let mut handle_event_arms = Vec::new();
+    let mut reset_arms = Vec::new();
+    let mut ramp_arms = Vec::new();
+    let mut advance_ramps_arms = Vec::new();
+    let mut outputs_arms = Vec::new();
+    let mut name_arms = Vec::new();
 
     for variant in &input.variants {
         let variant_name = &variant.ident;
+        // Checked above to be a single unnamed field
+        let module_type = match &variant.fields {
+            Fields::Unnamed(fields) => &fields.unnamed[0].ty,
+            _ => unreachable!(),
+        };
+
         is_ready_arms.push(quote! {
-            #enum_name::#variant_name(x) => <#variant_name as Module<SAMPLE_RATE>>::is_ready::<POINTS>(x, patchbay),
+            #enum_name::#variant_name(x) => <#module_type as Module<#sample_rate>>::is_ready::<POINTS>(x, patchbay),
         });
 
         process_arms.push(quote! {
-            #enum_name::#variant_name(x) => <#variant_name as Module<SAMPLE_RATE>>::process::<POINTS>(x, patchbay),
+            #enum_name::#variant_name(x) => <#module_type as Module<#sample_rate>>::process::<POINTS>(x, patchbay),
+        });
+
+        process_block_arms.push(quote! {
+            #enum_name::#variant_name(x) => <#module_type as Module<#sample_rate>>::process_block::<POINTS, FRAMES>(x, patchbay),
+        });
+
+        latency_arms.push(quote! {
+            #enum_name::#variant_name(x) => <#module_type as Module<#sample_rate>>::latency(x),
+        });
+
+        cost_hint_arms.push(quote! {
+            #enum_name::#variant_name(x) => <#module_type as Module<#sample_rate>>::cost_hint(x),
+        });
+
+        handle_event_arms.push(quote! {
+            #enum_name::#variant_name(x) => <#module_type as Module<#sample_rate>>::handle_event(x, event),
+        });
+
+        reset_arms.push(quote! {
+            #enum_name::#variant_name(x) => <#module_type as Module<#sample_rate>>::reset::<POINTS>(x, patchbay),
+        });
+
+        ramp_arms.push(quote! {
+            #enum_name::#variant_name(x) => <#module_type as Module<#sample_rate>>::ramp(x, param, target, samples),
+        });
+
+        advance_ramps_arms.push(quote! {
+            #enum_name::#variant_name(x) => <#module_type as Module<#sample_rate>>::advance_ramps(x),
+        });
+
+        outputs_arms.push(quote! {
+            #enum_name::#variant_name(x) => x.outputs(),
+        });
+
+        name_arms.push(quote! {
+            #enum_name::#variant_name(_) => stringify!(#variant_name),
         });
     }
 
+    let outputs_impl = outputs.map(|size| {
+        quote! {
+            impl #enum_name {
+                pub fn outputs(&self) -> [screech::Signal; #size] {
+                    match self {
+                        #(#outputs_arms)*
+                    }
+                }
+            }
+        }
+    });
+
     let gen = quote! {
         #input
 
-        impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for #enum_name {
+        impl #generics Module<#sample_rate> for #enum_name {
             fn is_ready<const POINTS: usize>(&self, patchbay: &Patchbay<POINTS>) -> bool {
                 match self {
                     #(#is_ready_arms)*
@@ -35,7 +153,63 @@ pub fn modularize(_attr: TokenStream, item: TokenStream) -> TokenStream {
                     #(#process_arms)*
                 }
             }
+
+            fn process_block<const POINTS: usize, const FRAMES: usize>(
+                &mut self,
+                patchbay: &mut screech::BlockPatchbay<POINTS, FRAMES>,
+            ) {
+                match self {
+                    #(#process_block_arms)*
+                }
+            }
+
+            fn latency(&self) -> usize {
+                match self {
+                    #(#latency_arms)*
+                }
+            }
+
+            fn cost_hint(&self) -> u32 {
+                match self {
+                    #(#cost_hint_arms)*
+                }
+            }
+
+            fn handle_event(&mut self, event: screech::Event) {
+                match self {
+                    #(#handle_event_arms)*
+                }
+            }
+
+            fn reset<const POINTS: usize>(&mut self, patchbay: &mut Patchbay<POINTS>) {
+                match self {
+                    #(#reset_arms)*
+                }
+            }
+
+            fn ramp(&mut self, param: screech::ParamId, target: f32, samples: usize) {
+                match self {
+                    #(#ramp_arms)*
+                }
+            }
+
+            fn advance_ramps(&mut self) {
+                match self {
+                    #(#advance_ramps_arms)*
+                }
+            }
+        }
+
+        impl #enum_name {
+            /// Name of the variant wrapping the module, e.g. for debugging.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    #(#name_arms)*
+                }
+            }
         }
+
+        #outputs_impl
     };
 
     gen.into()
@@ -0,0 +1,5 @@
+#[test]
+fn modularize_should_reject_invalid_variants() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
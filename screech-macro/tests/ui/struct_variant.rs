@@ -0,0 +1,10 @@
+use screech_macro::modularize;
+
+struct Oscillator;
+
+#[modularize]
+enum Modules {
+    Oscillator { module: Oscillator },
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use screech_macro::modularize;
+
+struct Oscillator;
+
+#[modularize]
+enum Modules {
+    Oscillator(Oscillator),
+    Empty,
+}
+
+fn main() {}
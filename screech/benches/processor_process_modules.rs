@@ -1,5 +1,5 @@
-use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
-use screech::modules::{Dummy, Mix, Oscillator};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use screech::modules::{Mix, Oscillator};
 use screech::{Module, Patchbay, Processor};
 use screech_macro::modularize;
 
@@ -11,10 +11,10 @@ const POINTS: usize = 4096;
 const SAMPLE_RATE: usize = 48000;
 
 #[modularize]
+#[allow(clippy::large_enum_variant)]
 enum Modules {
     Mix(Mix),
     Oscillator(Oscillator),
-    Dummy(Dummy),
 }
 
 pub fn bench(c: &mut Criterion) {
@@ -46,6 +46,16 @@ pub fn bench(c: &mut Criterion) {
         b.iter(|| process(black_box(&mut processor), black_box(&mut patchbay)))
     });
 
+    group.bench_function("process_samples", |b| {
+        b.iter(|| {
+            black_box(&mut processor).process_samples(
+                black_box(&mut patchbay),
+                BUFFER_SIZE,
+                |_, _| {},
+            )
+        })
+    });
+
     group.finish();
 }
 
@@ -58,8 +68,7 @@ fn process<
     processor: &mut Processor<SAMPLE_RATE, MODULES, M>,
     patchbay: &mut Patchbay<POINTS>,
 ) {
-    let buffer = [0.0; BUFFER_SIZE];
-    for i in 0..BUFFER_SIZE {
+    for _ in 0..BUFFER_SIZE {
         processor.process_modules(patchbay);
     }
 }
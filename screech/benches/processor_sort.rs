@@ -1,9 +1,8 @@
-use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
-use screech::modules::{Dummy, Mix, Oscillator};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use screech::modules::{Mix, Oscillator};
 use screech::{Module, Patchbay, Processor};
 use screech_macro::modularize;
 
-const BUFFER_SIZE: usize = 64;
 const MODULES: usize = 2048;
 const OSCILLATORS: usize = 16;
 const MIXERS: usize = 64;
@@ -11,10 +10,10 @@ const POINTS: usize = 4096;
 const SAMPLE_RATE: usize = 48000;
 
 #[modularize]
+#[allow(clippy::large_enum_variant)]
 enum Modules {
     Mix(Mix),
     Oscillator(Oscillator),
-    Dummy(Dummy),
 }
 
 pub fn bench(c: &mut Criterion) {
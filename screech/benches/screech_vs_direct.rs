@@ -45,26 +45,54 @@ fn screech_process_buffer<
 ) -> [f32; BUFFER_SIZE] {
     let mut buffer = [seed; BUFFER_SIZE];
 
-    for i in 0..BUFFER_SIZE {
-        patchbay.set(input_point, buffer[i]);
+    for sample in buffer.iter_mut() {
+        patchbay.set(input_point, *sample);
         processor.process_modules(patchbay);
-        buffer[i] = patchbay.get(output);
+        *sample = patchbay.get(output);
     }
 
     buffer
 }
 
+fn screech_process_block<
+    const SAMPLE_RATE: usize,
+    const P: usize,
+    const MODULES: usize,
+    M: Module<SAMPLE_RATE>,
+>(
+    seed: f32,
+    patchbay: &mut Patchbay<P>,
+    processor: &mut Processor<SAMPLE_RATE, MODULES, M>,
+    input_point: &mut PatchPoint,
+    output: Signal,
+) -> [f32; BUFFER_SIZE] {
+    let mut buffer = [seed; BUFFER_SIZE];
+
+    patchbay.set(input_point, seed);
+
+    processor.process_samples(patchbay, BUFFER_SIZE, |i, patchbay| {
+        buffer[i] = patchbay.get(output);
+
+        // Set the input for the next sample
+        if i + 1 < BUFFER_SIZE {
+            patchbay.set(input_point, buffer[i + 1]);
+        }
+    });
+
+    buffer
+}
+
 fn direct_process_buffer(seed: f32) -> [f32; BUFFER_SIZE] {
     let mut buffer = [seed; BUFFER_SIZE];
 
-    for i in 0..BUFFER_SIZE {
-        let calc1 = expensive(buffer[i], 1.0);
+    for sample in buffer.iter_mut() {
+        let calc1 = expensive(*sample, 1.0);
         let calc2 = expensive(calc1, 2.0);
         let calc3 = expensive(calc2, 3.0);
         let calc4 = expensive(calc3, 4.0);
         let calc5 = expensive(calc4, 5.0);
         let calc6 = expensive(calc5, 6.0);
-        buffer[i] = calc6;
+        *sample = calc6;
     }
 
     buffer
@@ -132,6 +160,18 @@ fn bench(c: &mut Criterion) {
         })
     });
 
+    group.bench_function("screech blocks of 64", |b| {
+        b.iter(|| {
+            screech_process_block(
+                black_box(1.0),
+                black_box(&mut patchbay),
+                black_box(&mut processor),
+                black_box(&mut input_point),
+                black_box(output),
+            )
+        })
+    });
+
     group.bench_function("direct", |b| {
         b.iter(|| direct_process_buffer(black_box(1.0)))
     });
@@ -19,7 +19,7 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let mut processor: Processor<SAMPLE_RATE, 1, _> = Processor::new([Some(oscillator)]);
 
-    for i in 0..BUFFER_SIZE {
+    for (i, sample) in buffer.iter_mut().enumerate() {
         // Change the waveshape every second
         let osc = processor.get_module_mut(0).unwrap();
 
@@ -30,12 +30,12 @@ fn main() -> Result<(), Box<dyn Error>> {
         } else if i > SAMPLE_RATE * 2 {
             let duty_cycle = (i % SAMPLE_RATE) as f32 / SAMPLE_RATE as f32;
             osc.output_pulse(duty_cycle);
-        } else if i > SAMPLE_RATE * 1 {
+        } else if i > SAMPLE_RATE {
             osc.output_triangle();
         }
 
         processor.process_modules(&mut patchbay);
-        buffer[i] = patchbay.get(output);
+        *sample = patchbay.get(output);
     }
 
     to_wav_file(&buffer, SAMPLE_RATE, "oscillator")?;
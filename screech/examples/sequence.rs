@@ -13,6 +13,7 @@ const MODULES: usize = 256;
 const PATCHPOINTS: usize = 256;
 
 #[modularize]
+#[allow(clippy::large_enum_variant)]
 enum Modules {
     Clock(Clock),
     Envelope(Envelope),
@@ -42,9 +43,9 @@ fn main() -> Result<(), Box<dyn Error>> {
     processor.insert_module(Modules::Envelope(envelope));
     processor.insert_module(Modules::Vca(vca));
 
-    for i in 0..BUFFER_SIZE {
+    for sample in buffer.iter_mut() {
         processor.process_modules(&mut patchbay);
-        buffer[i] = patchbay.get(output);
+        *sample = patchbay.get(output);
     }
 
     to_wav_file(&buffer, SAMPLE_RATE, "sequence")?;
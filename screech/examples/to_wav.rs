@@ -6,7 +6,7 @@ use wavv::{Data, Wav};
 
 pub fn to_wav_file(buffer: &[f32], sample_rate: usize, name: &str) -> Result<(), Box<dyn Error>> {
     let normalized: Vec<i16> = buffer
-        .into_iter()
+        .iter()
         .map(|x| (x * (i16::MAX as f32)) as i16)
         .collect();
 
@@ -14,7 +14,7 @@ pub fn to_wav_file(buffer: &[f32], sample_rate: usize, name: &str) -> Result<(),
 
     let file = format!("./examples/{}.wav", name);
     let path = Path::new(&file);
-    let mut file = File::create(&path)?;
+    let mut file = File::create(path)?;
     file.write_all(&wav.to_bytes())?;
 
     Ok(())
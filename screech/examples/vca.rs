@@ -40,9 +40,9 @@ fn main() -> Result<(), Box<dyn Error>> {
         Some(Modules::Vca(vca)),
     ]);
 
-    for i in 0..BUFFER_SIZE {
+    for sample in buffer.iter_mut() {
         processor.process_modules(&mut patchbay);
-        buffer[i] = patchbay.get(output);
+        *sample = patchbay.get(output);
     }
 
     to_wav_file(&buffer, SAMPLE_RATE, "vca")?;
@@ -0,0 +1,91 @@
+/// Keeps feedback paths (delays, reverbs) from lingering at denormal values.
+///
+/// Adds and then removes a tiny offset of ±1e-20 with alternating sign, values too small to
+/// survive that round trip are flushed to exactly zero. Arithmetic on denormal values is very
+/// slow on platforms without a flush-to-zero mode, so enable it there. Disabled by default.
+///
+/// ```
+/// use screech::AntiDenormal;
+///
+/// let mut anti_denormal = AntiDenormal::new();
+/// anti_denormal.set_enabled(true);
+///
+/// assert_eq!(anti_denormal.process(1e-30), 0.0);
+/// assert_eq!(anti_denormal.process(0.5), 0.5);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct AntiDenormal {
+    enabled: bool,
+    offset: f32,
+}
+
+impl AntiDenormal {
+    pub fn new() -> Self {
+        AntiDenormal {
+            enabled: false,
+            offset: 1e-20,
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Flush a feedback sample, call this once per sample on the value being fed back.
+    pub fn process(&mut self, sample: f32) -> f32 {
+        if !self.enabled {
+            return sample;
+        }
+
+        // Alternate the sign so no DC builds up
+        self.offset = -self.offset;
+
+        (sample + self.offset) - self.offset
+    }
+}
+
+impl Default for AntiDenormal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decay(anti_denormal: &mut AntiDenormal) -> (f32, bool) {
+        let mut state = 1.0;
+        let mut was_subnormal = false;
+
+        for _ in 0..200 {
+            state = anti_denormal.process(state * 0.5);
+            was_subnormal |= state.is_subnormal();
+        }
+
+        (state, was_subnormal)
+    }
+
+    #[test]
+    fn anti_denormal_should_pass_through_when_disabled() {
+        let (_, was_subnormal) = decay(&mut AntiDenormal::new());
+
+        assert!(was_subnormal);
+    }
+
+    #[test]
+    fn anti_denormal_should_flush_decaying_feedback_to_zero() {
+        let mut anti_denormal = AntiDenormal::new();
+        anti_denormal.set_enabled(true);
+
+        let (state, was_subnormal) = decay(&mut anti_denormal);
+
+        assert!(!was_subnormal);
+        assert_eq!(state, 0.0);
+    }
+}
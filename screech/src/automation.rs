@@ -0,0 +1,131 @@
+/// Sorted list of `(sample, value)` breakpoints describing a parameter over time.
+///
+/// Values in between breakpoints are linearly interpolated, before the first and after the last
+/// breakpoint the value is held. Use [`crate::modules::AutomationLane`] to play it back as a
+/// [`crate::Signal`].
+///
+/// ```
+/// use screech::Automation;
+///
+/// let mut automation: Automation<4> = Automation::new();
+///
+/// automation.add_breakpoint(0, 0.0);
+/// automation.add_breakpoint(100, 1.0);
+///
+/// assert_eq!(automation.value_at(50), 0.5);
+/// assert_eq!(automation.value_at(200), 1.0);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Automation<const BREAKPOINTS: usize> {
+    breakpoints: [(usize, f32); BREAKPOINTS],
+    length: usize,
+}
+
+impl<const BREAKPOINTS: usize> Automation<BREAKPOINTS> {
+    pub fn new() -> Self {
+        Automation {
+            breakpoints: [(0, 0.0); BREAKPOINTS],
+            length: 0,
+        }
+    }
+
+    /// Add a breakpoint keeping the list sorted, an existing breakpoint at the same sample is
+    /// replaced. Returns `false` if there is no room left.
+    pub fn add_breakpoint(&mut self, sample: usize, value: f32) -> bool {
+        let index = self.breakpoints[..self.length]
+            .iter()
+            .position(|&(s, _)| s >= sample)
+            .unwrap_or(self.length);
+
+        if index < self.length && self.breakpoints[index].0 == sample {
+            self.breakpoints[index].1 = value;
+            return true;
+        }
+
+        if self.length >= BREAKPOINTS {
+            return false;
+        }
+
+        // Shift the later breakpoints up to make room
+        self.breakpoints.copy_within(index..self.length, index + 1);
+        self.breakpoints[index] = (sample, value);
+        self.length += 1;
+
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.length = 0;
+    }
+
+    pub fn breakpoints(&self) -> &[(usize, f32)] {
+        &self.breakpoints[..self.length]
+    }
+
+    /// Get the interpolated value at a given sample index.
+    pub fn value_at(&self, sample: usize) -> f32 {
+        let breakpoints = self.breakpoints();
+
+        let (first, last) = match (breakpoints.first(), breakpoints.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return 0.0,
+        };
+
+        if sample <= first.0 {
+            return first.1;
+        }
+
+        if sample >= last.0 {
+            return last.1;
+        }
+
+        for pair in breakpoints.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+
+            if sample < to.0 {
+                let progress = (sample - from.0) as f32 / (to.0 - from.0) as f32;
+                return from.1 + (to.1 - from.1) * progress;
+            }
+        }
+
+        last.1
+    }
+}
+
+impl<const BREAKPOINTS: usize> Default for Automation<BREAKPOINTS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn automation_should_interpolate_between_breakpoints() {
+        let mut automation: Automation<4> = Automation::new();
+
+        automation.add_breakpoint(100, 1.0);
+        automation.add_breakpoint(0, 0.2);
+
+        assert_eq!(automation.breakpoints(), &[(0, 0.2), (100, 1.0)]);
+        assert_eq!(automation.value_at(0), 0.2);
+        assert!((automation.value_at(50) - 0.6).abs() < 1e-6);
+        assert_eq!(automation.value_at(100), 1.0);
+        assert_eq!(automation.value_at(1000), 1.0);
+    }
+
+    #[test]
+    fn automation_should_replace_and_limit_breakpoints() {
+        let mut automation: Automation<2> = Automation::new();
+
+        assert_eq!(automation.value_at(10), 0.0);
+        assert!(automation.add_breakpoint(10, 0.5));
+        assert_eq!(automation.value_at(0), 0.5);
+        assert!(automation.add_breakpoint(20, 0.5));
+        assert!(automation.add_breakpoint(10, 0.0));
+        assert!(!automation.add_breakpoint(30, 1.0));
+        assert_eq!(automation.breakpoints(), &[(10, 0.0), (20, 0.5)]);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{PatchPoint, Patchbay, Signal};
+
+/// Block oriented view on a [`Patchbay`], holding `N` frames of sample values for every point.
+///
+/// Used by [`crate::Processor::process_block`] so modules can process a whole block at once
+/// by overriding [`crate::Module::process_block`]. Points are allocated from the wrapped
+/// patchbay as usual.
+///
+/// ```
+/// use screech::{BlockPatchbay, Patchbay};
+///
+/// let mut patchbay: Patchbay<4> = Patchbay::new();
+/// let mut point = patchbay.point().unwrap();
+/// let mut block: BlockPatchbay<4, 2> = BlockPatchbay::new(patchbay);
+///
+/// block.set_block(&mut point, [0.1, 0.2]);
+/// assert_eq!(block.get_block(point.signal()), [0.1, 0.2]);
+/// ```
+pub struct BlockPatchbay<const P: usize, const N: usize> {
+    patchbay: Patchbay<P>,
+    blocks: [[f32; N]; P],
+}
+
+impl<const P: usize, const N: usize> BlockPatchbay<P, N> {
+    pub fn new(patchbay: Patchbay<P>) -> Self {
+        BlockPatchbay {
+            patchbay,
+            blocks: [[0.0; N]; P],
+        }
+    }
+
+    pub fn patchbay(&self) -> &Patchbay<P> {
+        &self.patchbay
+    }
+
+    pub fn patchbay_mut(&mut self) -> &mut Patchbay<P> {
+        &mut self.patchbay
+    }
+
+    /// Get the `N` sample values of a signal, signals of released points read as silence.
+    pub fn get_block(&self, signal: Signal) -> [f32; N] {
+        match (signal, self.patchbay.point_id(signal)) {
+            (_, Some(id)) => self.blocks[id],
+            (Signal::Fixed(s), _) => [s; N],
+            _ => [0.0; N],
+        }
+    }
+
+    /// Set the `N` sample values of a patchpoint.
+    pub fn set_block(&mut self, point: &mut PatchPoint, block: [f32; N]) {
+        if let Some(id) = self.patchbay.point_id(point.signal()) {
+            self.blocks[id] = block;
+        }
+    }
+
+    /// Copy a single frame into the wrapped patchbay, for processing it sample by sample.
+    pub fn load_frame(&mut self, frame: usize) {
+        for (sample, block) in self
+            .patchbay
+            .samples_mut()
+            .iter_mut()
+            .zip(self.blocks.iter())
+        {
+            *sample = block[frame];
+        }
+    }
+
+    /// Copy the sample values of the wrapped patchbay back into a single frame.
+    pub fn store_frame(&mut self, frame: usize) {
+        for (sample, block) in self
+            .patchbay
+            .samples_mut()
+            .iter()
+            .zip(self.blocks.iter_mut())
+        {
+            block[frame] = *sample;
+        }
+    }
+}
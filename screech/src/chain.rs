@@ -0,0 +1,138 @@
+/// Wire a linear chain of modules into a [`crate::Processor`].
+///
+/// Starting from an input [`crate::Signal`], every module gets the output of the previous one
+/// set as its input using its `set_input` method, is wrapped using the given path (e.g. an enum
+/// variant created by `#[modularize]`) and inserted into the processor. Evaluates to the
+/// output of the last module, or `None` if the processor ran out of room.
+///
+/// ```
+/// use screech::{chain, Module, Patchbay, Processor};
+/// use screech::modules::{Filter, Oscillator, Vca};
+/// use screech_macro::modularize;
+///
+/// #[modularize]
+/// enum Modules {
+///     Oscillator(Oscillator),
+///     Filter(Filter),
+///     Vca(Vca),
+/// }
+///
+/// const EMPTY: Option<Modules> = None;
+///
+/// let mut patchbay: Patchbay<8> = Patchbay::new();
+/// let mut processor: Processor<48_000, 8, Modules> = Processor::new([EMPTY; 8]);
+///
+/// let osc = Oscillator::new(patchbay.point().unwrap());
+/// let input = osc.output();
+/// processor.insert_module(Modules::Oscillator(osc));
+///
+/// let output = chain!(
+///     processor,
+///     input,
+///     Filter::new(patchbay.point().unwrap()) => Modules::Filter,
+///     Vca::new(patchbay.point().unwrap()) => Modules::Vca,
+/// );
+///
+/// assert!(output.is_some());
+/// ```
+#[macro_export]
+macro_rules! chain {
+    ($processor:expr, $input:expr, $($module:expr => $wrap:path),+ $(,)?) => {{
+        let mut signal: $crate::Signal = $input;
+        let mut inserted = true;
+
+        $(
+            let mut module = $module;
+            module.set_input(signal);
+            signal = module.output();
+            inserted &= $processor.insert_module($wrap(module)).is_some();
+        )+
+
+        if inserted {
+            Some(signal)
+        } else {
+            None
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modules::{Filter, Oscillator, Peak, Shelf};
+    use crate::{Module, Patchbay, Processor};
+    use screech_macro::modularize;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[modularize]
+    enum Modules {
+        Oscillator(Oscillator),
+        Filter(Filter),
+        Peak(Peak),
+        Shelf(Shelf),
+    }
+
+    #[test]
+    fn chain_should_match_a_hand_wired_chain() {
+        const EMPTY: Option<Modules> = None;
+
+        let mut patchbay: Patchbay<8> = Patchbay::new();
+        let mut processor: Processor<SAMPLE_RATE, 8, Modules> = Processor::new([EMPTY; 8]);
+
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+        let mut filter = Filter::new(patchbay.point().unwrap());
+        let mut peak = Peak::new(patchbay.point().unwrap());
+        let mut shelf = Shelf::new(patchbay.point().unwrap());
+
+        osc.output_saw();
+        filter.set_input(osc.output());
+        peak.set_input(filter.output());
+        shelf.set_input(peak.output());
+
+        let expected = shelf.output();
+
+        processor.insert_module(Modules::Shelf(shelf));
+        processor.insert_module(Modules::Peak(peak));
+        processor.insert_module(Modules::Filter(filter));
+        processor.insert_module(Modules::Oscillator(osc));
+
+        let mut chained_patchbay: Patchbay<8> = Patchbay::new();
+        let mut chained: Processor<SAMPLE_RATE, 8, Modules> = Processor::new([EMPTY; 8]);
+
+        let mut osc = Oscillator::new(chained_patchbay.point().unwrap());
+        osc.output_saw();
+        let input = osc.output();
+        chained.insert_module(Modules::Oscillator(osc));
+
+        let output = chain!(
+            chained,
+            input,
+            Filter::new(chained_patchbay.point().unwrap()) => Modules::Filter,
+            Peak::new(chained_patchbay.point().unwrap()) => Modules::Peak,
+            Shelf::new(chained_patchbay.point().unwrap()) => Modules::Shelf,
+        )
+        .unwrap();
+
+        for _ in 0..256 {
+            processor.process_modules(&mut patchbay);
+            chained.process_modules(&mut chained_patchbay);
+
+            assert_eq!(patchbay.get(expected), chained_patchbay.get(output));
+        }
+    }
+
+    #[test]
+    fn chain_should_return_none_when_the_processor_is_full() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut processor: Processor<SAMPLE_RATE, 1, Filter> = Processor::new([None]);
+
+        let output = chain!(
+            processor,
+            crate::Signal::Fixed(0.5),
+            Filter::new(patchbay.point().unwrap()) => core::convert::identity,
+            Filter::new(patchbay.point().unwrap()) => core::convert::identity,
+        );
+
+        assert!(output.is_none());
+    }
+}
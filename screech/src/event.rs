@@ -0,0 +1,104 @@
+/// Event that can be scheduled for a module using an [`EventQueue`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Event {
+    /// Start a note, `note` is a MIDI note number and `velocity` ranges from 0.0 to 1.0.
+    NoteOn { note: u8, velocity: f32 },
+    /// Release a note, `note` is a MIDI note number.
+    NoteOff { note: u8 },
+    /// Silence right away, sent to the other modules of a choke group when one of them receives
+    /// a [`Event::NoteOn`], see [`crate::Processor::set_choke_group`].
+    Choke,
+}
+
+/// Fixed size queue of events scheduled at a sample offset for a module.
+///
+/// Offsets are relative to the current sample of the queue, which advances every time the queue
+/// is passed to [`crate::Processor::process_modules_with_events`]. Events due at the same sample
+/// are delivered in the order they were pushed.
+///
+/// ```
+/// use screech::{Event, EventQueue};
+///
+/// let mut events: EventQueue<4> = EventQueue::new();
+///
+/// events.push(1, 0, Event::NoteOn { note: 60, velocity: 1.0 });
+/// assert_eq!(events.pop(), None);
+///
+/// events.advance();
+/// assert_eq!(events.pop(), Some((0, Event::NoteOn { note: 60, velocity: 1.0 })));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct EventQueue<const SIZE: usize> {
+    events: [(usize, usize, Event); SIZE],
+    length: usize,
+    time: usize,
+}
+
+impl<const SIZE: usize> EventQueue<SIZE> {
+    pub fn new() -> Self {
+        EventQueue {
+            events: [(0, 0, Event::NoteOff { note: 0 }); SIZE],
+            length: 0,
+            time: 0,
+        }
+    }
+
+    /// Schedule an event for the module at index `module`, `offset` samples from now.
+    /// Returns `false` if the queue is full.
+    pub fn push(&mut self, offset: usize, module: usize, event: Event) -> bool {
+        if self.length >= SIZE {
+            return false;
+        }
+
+        let time = self.time + offset;
+
+        // Insert after events at the same time to keep them in push order
+        let index = self.events[..self.length]
+            .iter()
+            .position(|&(t, _, _)| t > time)
+            .unwrap_or(self.length);
+
+        self.events.copy_within(index..self.length, index + 1);
+        self.events[index] = (time, module, event);
+        self.length += 1;
+
+        true
+    }
+
+    /// Take the next event due at the current sample, returning the module index and event.
+    pub fn pop(&mut self) -> Option<(usize, Event)> {
+        if self.length == 0 || self.events[0].0 > self.time {
+            return None;
+        }
+
+        let (_, module, event) = self.events[0];
+
+        self.events.copy_within(1..self.length, 0);
+        self.length -= 1;
+
+        Some((module, event))
+    }
+
+    /// Move on to the next sample.
+    pub fn advance(&mut self) {
+        self.time += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.length = 0;
+    }
+}
+
+impl<const SIZE: usize> Default for EventQueue<SIZE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,16 +1,48 @@
 //! Screech
 //!
 //! Opinionated real time audio library with a focus on performance and no_std environments.
+//!
+//! Enable the `libm` feature to use accurate math functions instead of the built-in
+//! approximations, see [`math`].
 
 #![no_std]
 
+// Lets the `modularize` macro refer to `screech::` paths from within this crate
+extern crate self as screech;
+
+mod anti_denormal;
+mod automation;
+mod block_patchbay;
+mod chain;
+mod event;
+pub mod math;
 mod module;
 pub mod modules;
 mod patchbay;
+mod phase_meter;
+pub mod pitch;
 mod processor;
+mod ramp;
+mod random;
+mod recorder;
+mod resample;
+mod scope;
 mod signal;
+mod sub_processor;
+#[cfg(test)]
+mod test_utils;
 
+pub use anti_denormal::AntiDenormal;
+pub use automation::Automation;
+pub use block_patchbay::BlockPatchbay;
+pub use event::{Event, EventQueue};
 pub use module::Module;
-pub use patchbay::{PatchPoint, Patchbay};
-pub use processor::Processor;
+pub use patchbay::{PatchPoint, Patchbay, StereoPatchPoint, StereoSignal};
+pub use phase_meter::PhaseMeter;
+pub use processor::{Processor, ProcessorError};
+pub use ramp::{ParamId, Ramp};
+pub use recorder::Recorder;
+pub use resample::{Downsampler, Upsampler};
+pub use scope::Scope;
 pub use signal::Signal;
+pub use sub_processor::SubProcessor;
@@ -0,0 +1,301 @@
+//! Math functions that are not available in `core`.
+//!
+//! With the `libm` feature enabled these forward to the [libm](https://docs.rs/libm) crate,
+//! otherwise dependency free approximations are used that are accurate enough for audio
+//! rate parameter calculations.
+
+use core::f32::consts::{FRAC_PI_2, LN_2, LOG2_E, PI, TAU};
+
+/// Sine of `x` in radians.
+pub fn sin(x: f32) -> f32 {
+    #[cfg(feature = "libm")]
+    return libm::sinf(x);
+
+    #[cfg(not(feature = "libm"))]
+    return approx::sin(x);
+}
+
+/// Cosine of `x` in radians.
+pub fn cos(x: f32) -> f32 {
+    #[cfg(feature = "libm")]
+    return libm::cosf(x);
+
+    #[cfg(not(feature = "libm"))]
+    return approx::cos(x);
+}
+
+/// Tangent of `x` in radians.
+pub fn tan(x: f32) -> f32 {
+    #[cfg(feature = "libm")]
+    return libm::tanf(x);
+
+    #[cfg(not(feature = "libm"))]
+    return approx::tan(x);
+}
+
+/// Hyperbolic tangent, useful as a soft clipping curve.
+pub fn tanh(x: f32) -> f32 {
+    #[cfg(feature = "libm")]
+    return libm::tanhf(x);
+
+    #[cfg(not(feature = "libm"))]
+    return approx::tanh(x);
+}
+
+/// Natural exponential `e^x`.
+pub fn exp(x: f32) -> f32 {
+    #[cfg(feature = "libm")]
+    return libm::expf(x);
+
+    #[cfg(not(feature = "libm"))]
+    return approx::exp(x);
+}
+
+/// Natural logarithm.
+pub fn ln(x: f32) -> f32 {
+    #[cfg(feature = "libm")]
+    return libm::logf(x);
+
+    #[cfg(not(feature = "libm"))]
+    return approx::ln(x);
+}
+
+/// Raise `x` to the power of `y`, only defined for positive values of `x` without `libm`.
+pub fn pow(x: f32, y: f32) -> f32 {
+    #[cfg(feature = "libm")]
+    return libm::powf(x, y);
+
+    #[cfg(not(feature = "libm"))]
+    return approx::pow(x, y);
+}
+
+/// Square root.
+pub fn sqrt(x: f32) -> f32 {
+    #[cfg(feature = "libm")]
+    return libm::sqrtf(x);
+
+    #[cfg(not(feature = "libm"))]
+    return approx::sqrt(x);
+}
+
+/// Largest integer value less than or equal to `x`.
+pub fn floor(x: f32) -> f32 {
+    #[cfg(feature = "libm")]
+    return libm::floorf(x);
+
+    #[cfg(not(feature = "libm"))]
+    return approx::floor(x);
+}
+
+/// One-pole smoothing coefficient reaching ~63% of a step after `seconds`.
+pub(crate) fn one_pole_coefficient(seconds: f32, sample_rate: usize) -> f32 {
+    let samples = seconds * sample_rate as f32;
+
+    if samples > 1.0 {
+        1.0 - exp(-1.0 / samples)
+    } else {
+        1.0
+    }
+}
+
+#[cfg_attr(feature = "libm", allow(dead_code))]
+mod approx {
+    use super::*;
+
+    pub fn floor(x: f32) -> f32 {
+        // Values this large have no fractional part
+        if x.abs() >= 8_388_608.0 || x.is_nan() {
+            return x;
+        }
+
+        let truncated = x as i32 as f32;
+
+        if truncated > x {
+            truncated - 1.0
+        } else {
+            truncated
+        }
+    }
+
+    pub fn sin(x: f32) -> f32 {
+        // Wrap into -PI..PI
+        let x = x - TAU * floor((x + PI) / TAU);
+
+        // Mirror into -PI/2..PI/2
+        let x = if x > FRAC_PI_2 {
+            PI - x
+        } else if x < -FRAC_PI_2 {
+            -PI - x
+        } else {
+            x
+        };
+
+        // Taylor series up to x^11
+        let x2 = x * x;
+        x * (1.0
+            - x2 / 6.0
+                * (1.0 - x2 / 20.0 * (1.0 - x2 / 42.0 * (1.0 - x2 / 72.0 * (1.0 - x2 / 110.0)))))
+    }
+
+    pub fn cos(x: f32) -> f32 {
+        sin(x + FRAC_PI_2)
+    }
+
+    pub fn tan(x: f32) -> f32 {
+        sin(x) / cos(x)
+    }
+
+    pub fn exp(x: f32) -> f32 {
+        // Convert to a power of two and split into the integer and fractional part
+        let y = x * LOG2_E;
+
+        if y > 128.0 {
+            return f32::INFINITY;
+        }
+
+        if y < -126.0 {
+            return 0.0;
+        }
+
+        let whole = floor(y);
+        let fraction = (y - whole) * LN_2;
+
+        // Taylor series of e^fraction, with fraction between 0.0 and ln(2)
+        let mut term = 1.0;
+        let mut sum = 1.0;
+
+        for n in 1..10 {
+            term *= fraction / n as f32;
+            sum += term;
+        }
+
+        // Construct 2^whole directly from the exponent bits
+        let exponent = ((whole as i32 + 127) as u32) << 23;
+
+        sum * f32::from_bits(exponent)
+    }
+
+    pub fn ln(x: f32) -> f32 {
+        if x < 0.0 || x.is_nan() {
+            return f32::NAN;
+        }
+
+        if x == 0.0 {
+            return f32::NEG_INFINITY;
+        }
+
+        if x.is_infinite() {
+            return x;
+        }
+
+        // Normalize subnormal values
+        let (x, offset) = if x < f32::MIN_POSITIVE {
+            (x * 16_777_216.0, -24.0)
+        } else {
+            (x, 0.0)
+        };
+
+        // Split into a mantissa between sqrt(0.5) and sqrt(2) and an exponent
+        let bits = x.to_bits();
+        let mut exponent = ((bits >> 23) & 0xff) as i32 - 127;
+        let mut mantissa = f32::from_bits((bits & 0x007f_ffff) | 0x3f80_0000);
+
+        if mantissa > core::f32::consts::SQRT_2 {
+            mantissa *= 0.5;
+            exponent += 1;
+        }
+
+        // ln(m) = 2 * atanh((m - 1) / (m + 1))
+        let s = (mantissa - 1.0) / (mantissa + 1.0);
+        let s2 = s * s;
+        let series = 2.0 * s * (1.0 + s2 * (1.0 / 3.0 + s2 * (1.0 / 5.0 + s2 * (1.0 / 7.0))));
+
+        series + (exponent as f32 + offset) * LN_2
+    }
+
+    pub fn pow(x: f32, y: f32) -> f32 {
+        if x == 0.0 {
+            return if y == 0.0 { 1.0 } else { 0.0 };
+        }
+
+        exp(y * ln(x))
+    }
+
+    pub fn sqrt(x: f32) -> f32 {
+        if x < 0.0 || x.is_nan() {
+            return f32::NAN;
+        }
+
+        if x == 0.0 || x.is_infinite() {
+            return x;
+        }
+
+        // Initial guess by halving the exponent, refined with Newton's method
+        let mut y = f32::from_bits((x.to_bits() >> 1) + 0x1fbd_1df5);
+
+        for _ in 0..3 {
+            y = 0.5 * (y + x / y);
+        }
+
+        y
+    }
+
+    pub fn tanh(x: f32) -> f32 {
+        if x.abs() > 9.0 {
+            return x.signum();
+        }
+
+        if x.abs() < 0.01 {
+            return x - x * x * x / 3.0;
+        }
+
+        let e = exp(2.0 * x);
+        (e - 1.0) / (e + 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::approx;
+
+    fn assert_close(name: &str, f: fn(f32) -> f32, reference: fn(f32) -> f32, from: f32, to: f32) {
+        for i in 0..=1000 {
+            let x = from + (to - from) * i as f32 / 1000.0;
+            let expected = reference(x);
+            let error = (f(x) - expected).abs() / expected.abs().max(1.0);
+            assert!(error < 1e-5, "{}({}) off by {}", name, x, error);
+        }
+    }
+
+    #[test]
+    fn approximations_should_be_close_to_std() {
+        assert_close("sin", approx::sin, f32::sin, -20.0, 20.0);
+        assert_close("cos", approx::cos, f32::cos, -20.0, 20.0);
+        assert_close("tan", approx::tan, f32::tan, -1.5, 1.5);
+        assert_close("tanh", approx::tanh, f32::tanh, -10.0, 10.0);
+        assert_close("exp", approx::exp, f32::exp, -20.0, 20.0);
+        assert_close("ln", approx::ln, f32::ln, 0.001, 1000.0);
+        assert_close("sqrt", approx::sqrt, f32::sqrt, 0.0, 1000.0);
+        assert_close("floor", approx::floor, f32::floor, -100.0, 100.0);
+        assert_close("pow", |x| approx::pow(x, 2.5), |x| x.powf(2.5), 0.001, 10.0);
+    }
+
+    #[cfg(feature = "libm")]
+    #[test]
+    fn libm_should_be_at_least_as_accurate_as_the_approximations() {
+        let mut approx_error = 0.0;
+        let mut libm_error = 0.0;
+
+        for i in 0..=1000 {
+            let x = -10.0 + 20.0 * i as f32 / 1000.0;
+            let expected = (x as f64).sin();
+
+            approx_error += (approx::sin(x) as f64 - expected).abs();
+            libm_error += (super::sin(x) as f64 - expected).abs();
+        }
+
+        assert!(libm_error <= approx_error);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::Patchbay;
+use crate::{BlockPatchbay, Event, ParamId, Patchbay};
 
 /// Reads and/or writes signals to a [`Patchbay`] instance.
 ///
@@ -47,4 +47,50 @@ pub trait Module<const SAMPLE_RATE: usize> {
     /// Process the module changing internal state and setting outputs in the [`Patchbay`]
     /// using the [`Patchbay::set`] method.
     fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>);
+
+    /// Process `N` frames at once, used by [`crate::Processor::process_block`].
+    ///
+    /// Defaults to calling [`Module::process`] for every frame, which copies all sample values
+    /// in and out of the [`BlockPatchbay`]. Override it for modules that can work on whole
+    /// blocks using [`BlockPatchbay::get_block`] and [`BlockPatchbay::set_block`].
+    fn process_block<const P: usize, const N: usize>(
+        &mut self,
+        patchbay: &mut BlockPatchbay<P, N>,
+    ) {
+        for frame in 0..N {
+            patchbay.load_frame(frame);
+            self.process(patchbay.patchbay_mut());
+            patchbay.store_frame(frame);
+        }
+    }
+
+    /// Number of samples the module delays its input by before it reaches the output.
+    ///
+    /// Modules that introduce latency (lookahead, buffering) should override this so parallel
+    /// chains can be aligned, see [`crate::Processor::total_latency`].
+    fn latency(&self) -> usize {
+        0
+    }
+
+    /// Rough relative cost of processing a sample, where 1 is a simple module like an
+    /// oscillator. Used for budgeting using [`crate::Processor::total_cost`].
+    fn cost_hint(&self) -> u32 {
+        1
+    }
+
+    /// Receive an event scheduled through an [`crate::EventQueue`], called right before
+    /// [`Module::process`] for the sample the event was scheduled at.
+    fn handle_event(&mut self, _event: Event) {}
+
+    /// Return to the initial state, e.g. clearing oscillator phases and delay buffers between
+    /// disconnected render passes, see [`crate::Processor::reset_modules`].
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {}
+
+    /// Move a parameter linearly to `target` over `samples` samples, replacing a running ramp
+    /// of the same parameter. Unknown parameters are ignored, as are all parameters of modules
+    /// without ramps. See [`crate::Ramp`] for implementing ramps.
+    fn ramp(&mut self, _param: ParamId, _target: f32, _samples: usize) {}
+
+    /// Advance all running ramps by one sample, see [`crate::Processor::advance_ramps`].
+    fn advance_ramps(&mut self) {}
 }
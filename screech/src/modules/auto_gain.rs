@@ -0,0 +1,195 @@
+use crate::math;
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Automatic gain control leveling the input towards a target RMS level.
+///
+/// The input RMS is measured over a window and the gain moves slowly towards the amount needed
+/// to reach the target, a long adaptation time avoids audible pumping. The gain is limited by
+/// [`AutoGain::set_max_gain`] so silence or noise floors don't get boosted indefinitely.
+pub struct AutoGain {
+    input: Signal,
+    output: PatchPoint,
+    target_rms: f32,
+    window: f32,
+    adaptation: f32,
+    max_gain: f32,
+    mean_square: f32,
+    gain: f32,
+    window_coefficient: f32,
+    adaptation_coefficient: f32,
+    update: bool,
+}
+
+impl AutoGain {
+    pub fn new(output: PatchPoint) -> Self {
+        AutoGain {
+            input: Signal::None,
+            output,
+            target_rms: 0.25,
+            window: 0.3,
+            adaptation: 2.0,
+            max_gain: 10.0,
+            mean_square: 0.0,
+            gain: 1.0,
+            window_coefficient: 0.0,
+            adaptation_coefficient: 0.0,
+            update: true,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    pub fn set_target_rms(&mut self, target_rms: f32) -> &mut Self {
+        self.target_rms = target_rms;
+        self
+    }
+
+    pub fn get_target_rms(&self) -> f32 {
+        self.target_rms
+    }
+
+    /// Set the window the input RMS is measured over in seconds.
+    pub fn set_window(&mut self, seconds: f32) -> &mut Self {
+        self.window = seconds;
+        self.update = true;
+        self
+    }
+
+    pub fn get_window(&self) -> f32 {
+        self.window
+    }
+
+    /// Set how quickly the gain follows changes in level in seconds.
+    pub fn set_adaptation(&mut self, seconds: f32) -> &mut Self {
+        self.adaptation = seconds;
+        self.update = true;
+        self
+    }
+
+    pub fn get_adaptation(&self) -> f32 {
+        self.adaptation
+    }
+
+    pub fn set_max_gain(&mut self, max_gain: f32) -> &mut Self {
+        self.max_gain = max_gain;
+        self
+    }
+
+    pub fn get_max_gain(&self) -> f32 {
+        self.max_gain
+    }
+
+    /// Gain currently applied to the input.
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for AutoGain {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        if self.update {
+            self.window_coefficient = math::one_pole_coefficient(self.window, SAMPLE_RATE);
+            self.adaptation_coefficient = math::one_pole_coefficient(self.adaptation, SAMPLE_RATE);
+            self.update = false;
+        }
+
+        let input = patchbay.get(self.input);
+
+        self.mean_square += (input * input - self.mean_square) * self.window_coefficient;
+
+        let rms = math::sqrt(self.mean_square);
+        let target_gain = if rms > 0.0 {
+            (self.target_rms / rms).min(self.max_gain)
+        } else {
+            self.max_gain
+        };
+
+        self.gain += (target_gain - self.gain) * self.adaptation_coefficient;
+
+        patchbay.set(&mut self.output, input * self.gain);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.mean_square = 0.0;
+        self.gain = 1.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::f32::consts::PI;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    // Run a sine through the module for a few seconds and measure the output RMS at the end
+    fn level(
+        auto_gain: &mut AutoGain,
+        patchbay: &mut Patchbay<2>,
+        input: &mut PatchPoint,
+        amplitude: f32,
+    ) -> f32 {
+        let mut energy = 0.0;
+        let length = SAMPLE_RATE * 4;
+        let measured = SAMPLE_RATE / 10;
+
+        for i in 0..length {
+            let sample = amplitude * math::sin(2.0 * PI * 440.0 * i as f32 / SAMPLE_RATE as f32);
+            patchbay.set(input, sample);
+            Module::<SAMPLE_RATE>::process(auto_gain, patchbay);
+
+            if i >= length - measured {
+                let output = patchbay.get(auto_gain.output());
+                energy += output * output;
+            }
+        }
+
+        math::sqrt(energy / measured as f32)
+    }
+
+    #[test]
+    fn auto_gain_should_converge_to_the_target_level() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut auto_gain = AutoGain::new(patchbay.point().unwrap());
+
+        auto_gain
+            .set_input(input.signal())
+            .set_target_rms(0.25)
+            .set_adaptation(0.5);
+
+        let quiet = level(&mut auto_gain, &mut patchbay, &mut input, 0.05);
+        assert!((quiet - 0.25).abs() < 0.025);
+
+        let loud = level(&mut auto_gain, &mut patchbay, &mut input, 0.9);
+        assert!((loud - 0.25).abs() < 0.025);
+    }
+
+    #[test]
+    fn auto_gain_should_limit_the_gain() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut auto_gain = AutoGain::new(patchbay.point().unwrap());
+
+        auto_gain
+            .set_input(input.signal())
+            .set_adaptation(0.5)
+            .set_max_gain(2.0);
+
+        let quiet = level(&mut auto_gain, &mut patchbay, &mut input, 0.01);
+
+        assert!((auto_gain.gain() - 2.0).abs() < 0.01);
+        assert!(quiet < 0.25);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{Automation, Module, PatchPoint, Patchbay, Signal};
+
+/// Plays back an [`Automation`] as a signal, advancing one sample per process call.
+pub struct AutomationLane<const BREAKPOINTS: usize> {
+    automation: Automation<BREAKPOINTS>,
+    position: usize,
+    output: PatchPoint,
+}
+
+impl<const BREAKPOINTS: usize> AutomationLane<BREAKPOINTS> {
+    pub fn new(automation: Automation<BREAKPOINTS>, output: PatchPoint) -> Self {
+        AutomationLane {
+            automation,
+            position: 0,
+            output,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn automation(&self) -> &Automation<BREAKPOINTS> {
+        &self.automation
+    }
+
+    pub fn automation_mut(&mut self) -> &mut Automation<BREAKPOINTS> {
+        &mut self.automation
+    }
+
+    /// Move the playhead to a sample index.
+    pub fn set_position(&mut self, position: usize) -> &mut Self {
+        self.position = position;
+        self
+    }
+
+    pub fn get_position(&self) -> usize {
+        self.position
+    }
+}
+
+impl<const SAMPLE_RATE: usize, const BREAKPOINTS: usize> Module<SAMPLE_RATE>
+    for AutomationLane<BREAKPOINTS>
+{
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        patchbay.set(&mut self.output, self.automation.value_at(self.position));
+        self.position += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn automation_lane_should_output_the_automated_value() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut automation: Automation<2> = Automation::new();
+
+        automation.add_breakpoint(0, 0.0);
+        automation.add_breakpoint(100, 1.0);
+
+        let mut lane = AutomationLane::new(automation, patchbay.point().unwrap());
+
+        for i in 0..=50 {
+            Module::<SAMPLE_RATE>::process(&mut lane, &mut patchbay);
+            assert!((patchbay.get(lane.output()) - i as f32 / 100.0).abs() < 1e-6);
+        }
+
+        lane.set_position(150);
+        Module::<SAMPLE_RATE>::process(&mut lane, &mut patchbay);
+        assert_eq!(patchbay.get(lane.output()), 1.0);
+    }
+}
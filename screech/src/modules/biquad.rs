@@ -0,0 +1,192 @@
+use crate::math;
+use core::f32::consts::PI;
+
+/// Second order IIR filter section with RBJ cookbook coefficient calculations.
+///
+/// Processed using the transposed direct form II, changing coefficients keeps the internal
+/// state intact to avoid clicks when sweeping parameters.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    /// Creates a filter that passes the signal through unchanged.
+    pub fn new() -> Self {
+        Biquad {
+            b0: 1.0,
+            b1: 0.0,
+            b2: 0.0,
+            a1: 0.0,
+            a2: 0.0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        let output = self.b0 * input + self.z1;
+
+        self.z1 = self.b1 * input - self.a1 * output + self.z2;
+        self.z2 = self.b2 * input - self.a2 * output;
+
+        output
+    }
+
+    /// Clears the internal state, keeping the coefficients.
+    pub fn clear(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+
+    fn set_coefficients(&mut self, b0: f32, b1: f32, b2: f32, a0: f32, a1: f32, a2: f32) {
+        self.b0 = b0 / a0;
+        self.b1 = b1 / a0;
+        self.b2 = b2 / a0;
+        self.a1 = a1 / a0;
+        self.a2 = a2 / a0;
+    }
+
+    pub fn set_low_shelf(&mut self, sample_rate: usize, frequency: f32, gain_db: f32) {
+        let (a, cos, alpha) = shelf_parameters(sample_rate, frequency, gain_db);
+        let sqrt_alpha = 2.0 * math::sqrt(a) * alpha;
+
+        self.set_coefficients(
+            a * ((a + 1.0) - (a - 1.0) * cos + sqrt_alpha),
+            2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+            a * ((a + 1.0) - (a - 1.0) * cos - sqrt_alpha),
+            (a + 1.0) + (a - 1.0) * cos + sqrt_alpha,
+            -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+            (a + 1.0) + (a - 1.0) * cos - sqrt_alpha,
+        );
+    }
+
+    pub fn set_high_shelf(&mut self, sample_rate: usize, frequency: f32, gain_db: f32) {
+        let (a, cos, alpha) = shelf_parameters(sample_rate, frequency, gain_db);
+        let sqrt_alpha = 2.0 * math::sqrt(a) * alpha;
+
+        self.set_coefficients(
+            a * ((a + 1.0) + (a - 1.0) * cos + sqrt_alpha),
+            -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+            a * ((a + 1.0) + (a - 1.0) * cos - sqrt_alpha),
+            (a + 1.0) - (a - 1.0) * cos + sqrt_alpha,
+            2.0 * ((a - 1.0) - (a + 1.0) * cos),
+            (a + 1.0) - (a - 1.0) * cos - sqrt_alpha,
+        );
+    }
+
+    pub fn set_low_pass(&mut self, sample_rate: usize, frequency: f32, q: f32) {
+        let w0 = angular_frequency(sample_rate, frequency);
+        let cos = math::cos(w0);
+        let alpha = math::sin(w0) / (2.0 * q.max(0.01));
+
+        self.set_coefficients(
+            (1.0 - cos) / 2.0,
+            1.0 - cos,
+            (1.0 - cos) / 2.0,
+            1.0 + alpha,
+            -2.0 * cos,
+            1.0 - alpha,
+        );
+    }
+
+    pub fn set_high_pass(&mut self, sample_rate: usize, frequency: f32, q: f32) {
+        let w0 = angular_frequency(sample_rate, frequency);
+        let cos = math::cos(w0);
+        let alpha = math::sin(w0) / (2.0 * q.max(0.01));
+
+        self.set_coefficients(
+            (1.0 + cos) / 2.0,
+            -(1.0 + cos),
+            (1.0 + cos) / 2.0,
+            1.0 + alpha,
+            -2.0 * cos,
+            1.0 - alpha,
+        );
+    }
+
+    pub fn set_peak(&mut self, sample_rate: usize, frequency: f32, q: f32, gain_db: f32) {
+        let a = math::pow(10.0, gain_db / 40.0);
+        let w0 = angular_frequency(sample_rate, frequency);
+        let cos = math::cos(w0);
+        let alpha = math::sin(w0) / (2.0 * q.max(0.01));
+
+        self.set_coefficients(
+            1.0 + alpha * a,
+            -2.0 * cos,
+            1.0 - alpha * a,
+            1.0 + alpha / a,
+            -2.0 * cos,
+            1.0 - alpha / a,
+        );
+    }
+}
+
+/// Fourth order Linkwitz-Riley crossover splitting a signal into a low and high band.
+///
+/// Made from two cascaded Butterworth sections per band, the bands sum back to an allpassed
+/// version of the input with a flat magnitude response.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct LinkwitzRiley {
+    low: [Biquad; 2],
+    high: [Biquad; 2],
+}
+
+impl LinkwitzRiley {
+    pub fn new() -> Self {
+        LinkwitzRiley {
+            low: [Biquad::new(); 2],
+            high: [Biquad::new(); 2],
+        }
+    }
+
+    pub fn set_frequency(&mut self, sample_rate: usize, frequency: f32) {
+        let q = core::f32::consts::FRAC_1_SQRT_2;
+
+        for filter in self.low.iter_mut() {
+            filter.set_low_pass(sample_rate, frequency, q);
+        }
+
+        for filter in self.high.iter_mut() {
+            filter.set_high_pass(sample_rate, frequency, q);
+        }
+    }
+
+    /// Split a sample into its `(low, high)` bands.
+    pub fn process(&mut self, input: f32) -> (f32, f32) {
+        let low = self.low[0].process(input);
+        let low = self.low[1].process(low);
+
+        let high = self.high[0].process(input);
+        let high = self.high[1].process(high);
+
+        (low, high)
+    }
+
+    pub fn clear(&mut self) {
+        for filter in self.low.iter_mut().chain(self.high.iter_mut()) {
+            filter.clear();
+        }
+    }
+}
+
+/// Angular frequency of `frequency` clamped just below nyquist.
+fn angular_frequency(sample_rate: usize, frequency: f32) -> f32 {
+    let nyquist = sample_rate as f32 * 0.5;
+    2.0 * PI * frequency.clamp(1.0, nyquist * 0.99) / sample_rate as f32
+}
+
+/// Amplitude, cosine of the angular frequency and alpha for a shelf with a slope of 1.
+fn shelf_parameters(sample_rate: usize, frequency: f32, gain_db: f32) -> (f32, f32, f32) {
+    let a = math::pow(10.0, gain_db / 40.0);
+    let w0 = angular_frequency(sample_rate, frequency);
+    let alpha = math::sin(w0) / 2.0 * core::f32::consts::SQRT_2;
+
+    (a, math::cos(w0), alpha)
+}
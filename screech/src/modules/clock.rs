@@ -1,36 +1,338 @@
 use crate::{Module, PatchPoint, Patchbay, Signal};
 
+// Maximum amount of division and multiplication outputs
+const DIVISIONS: usize = 8;
+
 /// Pulse generator, BPM based
+///
+/// The clock starts at the beginning of a pulse, so the first processed sample is high unless a
+/// start delay has been set using [`Clock::set_start_delay`]. Every pulse spans two beats and by
+/// default stays high for the first one, see [`Clock::set_pulse_width`] and [`Clock::set_swing`]
+/// for shaping the pulses.
+///
+/// Up to 8 extra outputs running at a division or multiple of the tempo can be added using
+/// [`Clock::add_division`], these are derived from the same phase so they never drift apart.
 pub struct Clock {
     output: PatchPoint,
+    divisions: [Option<(i32, PatchPoint)>; DIVISIONS],
     bpm: f32,
-    value: f32,
+    value: f64,
+    pairs: u32,
+    pulse_width: f32,
+    swing: f32,
+    start_delay: f32,
+    elapsed: usize,
 }
 
 impl Clock {
     pub fn new(output: PatchPoint, bpm: f32) -> Self {
         Clock {
             output,
+            divisions: Default::default(),
             bpm,
             value: 0.0,
+            pairs: 0,
+            pulse_width: 0.5,
+            swing: 0.0,
+            start_delay: 0.0,
+            elapsed: 0,
         }
     }
 
     pub fn output(&self) -> Signal {
         self.output.signal()
     }
+
+    /// Hold the output low for `ms` milliseconds before the first pulse.
+    pub fn set_start_delay(&mut self, ms: f32) -> &mut Self {
+        self.start_delay = ms;
+        self
+    }
+
+    pub fn get_start_delay(&self) -> f32 {
+        self.start_delay
+    }
+
+    /// Set the fraction of a pulse the output stays high, defaults to 0.5.
+    pub fn set_pulse_width(&mut self, fraction: f32) -> &mut Self {
+        self.pulse_width = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn get_pulse_width(&self) -> f32 {
+        self.pulse_width
+    }
+
+    /// Delay every other pulse by a fraction of a pulse, swung pulses are shortened by the same
+    /// fraction to keep a gap before the next pulse.
+    pub fn set_swing(&mut self, amount: f32) -> &mut Self {
+        self.swing = amount.clamp(0.0, 0.99);
+        self
+    }
+
+    pub fn get_swing(&self) -> f32 {
+        self.swing
+    }
+
+    /// Add an output pulsing on every `ratio`th pulse, or `-ratio` times per pulse for negative
+    /// ratios. Divided outputs follow the pulses of the main output, multiplied outputs use the
+    /// same pulse width without swing. Returns `false` when all division outputs are in use.
+    pub fn add_division(&mut self, ratio: i32, output: PatchPoint) -> bool {
+        match self.divisions.iter_mut().find(|d| d.is_none()) {
+            Some(slot) => {
+                *slot = Some((ratio, output));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Output of the division added with the given ratio, [`Signal::None`] if there is none.
+    pub fn output_division(&self, ratio: i32) -> Signal {
+        self.divisions
+            .iter()
+            .flatten()
+            .find(|(r, _)| *r == ratio)
+            .map_or(Signal::None, |(_, point)| point.signal())
+    }
 }
 
 impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Clock {
     fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
-        self.value += (1.0 / SAMPLE_RATE as f32) * (self.bpm / 60.0);
+        let delay = (self.start_delay / 1000.0 * SAMPLE_RATE as f32) as usize;
+
+        if self.elapsed < delay {
+            self.elapsed += 1;
+            patchbay.set(&mut self.output, 0.0);
+
+            for (_, point) in self.divisions.iter_mut().flatten() {
+                patchbay.set(point, 0.0);
+            }
+
+            return;
+        }
+
+        // Step through a pair of pulses of two beats each, the second one of which gets swung.
+        // The phase is accumulated in double precision to keep the pulses from drifting.
+        self.value += (1.0 / SAMPLE_RATE as f64) * (self.bpm as f64 / 120.0);
 
         if self.value >= 2.0 {
             self.value -= 2.0;
+            self.pairs = self.pairs.wrapping_add(1);
         }
 
-        let output = if self.value > 1.0 { 0.0 } else { 1.0 };
+        let value = self.value as f32;
+
+        let position = if value < 1.0 {
+            value
+        } else {
+            value - 1.0 - self.swing
+        };
+
+        let pulse_width = if value < 1.0 {
+            self.pulse_width
+        } else {
+            self.pulse_width * (1.0 - self.swing)
+        };
+
+        let output = if (0.0..pulse_width).contains(&position) {
+            1.0
+        } else {
+            0.0
+        };
 
         patchbay.set(&mut self.output, output);
+
+        let pulse = self.pairs.wrapping_mul(2) + value as u32;
+
+        for (ratio, point) in self.divisions.iter_mut().flatten() {
+            let division = if *ratio < -1 {
+                let phase = value * -*ratio as f32;
+                let position = phase - (phase as u32) as f32;
+
+                if position < self.pulse_width {
+                    1.0
+                } else {
+                    0.0
+                }
+            } else if pulse % ratio.unsigned_abs().max(1) == 0 {
+                output
+            } else {
+                0.0
+            };
+
+            patchbay.set(point, division);
+        }
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.value = 0.0;
+        self.pairs = 0;
+        self.elapsed = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn clock_should_start_with_a_pulse() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut clock = Clock::new(patchbay.point().unwrap(), 120.0);
+
+        Module::<SAMPLE_RATE>::process(&mut clock, &mut patchbay);
+        assert_eq!(patchbay.get(clock.output()), 1.0);
+    }
+
+    #[test]
+    fn clock_should_delay_the_first_pulse() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut clock = Clock::new(patchbay.point().unwrap(), 120.0);
+
+        clock.set_start_delay(10.0);
+
+        // 10ms at 48kHz
+        for _ in 0..480 {
+            Module::<SAMPLE_RATE>::process(&mut clock, &mut patchbay);
+            assert_eq!(patchbay.get(clock.output()), 0.0);
+        }
+
+        Module::<SAMPLE_RATE>::process(&mut clock, &mut patchbay);
+        assert_eq!(patchbay.get(clock.output()), 1.0);
+    }
+
+    // Rising edges and high samples for 4 pulses at 120 BPM, 48000 samples per pulse. The phase
+    // accumulates small rounding errors so timings are checked within a millisecond.
+    fn pulses(clock: &mut Clock, patchbay: &mut Patchbay<1>) -> ([usize; 4], [usize; 4]) {
+        let (mut edges, mut highs) = ([0; 4], [0; 4]);
+        let mut previous = 0.0;
+        let mut pulse = 0;
+
+        for i in 0..SAMPLE_RATE * 5 {
+            Module::<SAMPLE_RATE>::process(clock, patchbay);
+            let value = patchbay.get(clock.output());
+
+            if value == 1.0 && previous == 0.0 {
+                if pulse == 4 {
+                    break;
+                }
+
+                edges[pulse] = i;
+                pulse += 1;
+            }
+
+            if value == 1.0 {
+                highs[pulse - 1] += 1;
+            }
+
+            previous = value;
+        }
+
+        (edges, highs)
+    }
+
+    #[test]
+    fn clock_should_default_to_a_pulse_every_two_beats() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut clock = Clock::new(patchbay.point().unwrap(), 120.0);
+
+        let (edges, highs) = pulses(&mut clock, &mut patchbay);
+
+        // High for one beat and low for the next
+        for (pulse, edge) in edges.iter().enumerate() {
+            assert!((*edge as i32 - pulse as i32 * 48_000).abs() <= 48);
+        }
+
+        for high in highs {
+            assert!((high as i32 - 24_000).abs() <= 48);
+        }
+    }
+
+    #[test]
+    fn clock_should_stay_high_for_the_pulse_width() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut clock = Clock::new(patchbay.point().unwrap(), 120.0);
+
+        clock.set_pulse_width(0.25);
+        let (edges, highs) = pulses(&mut clock, &mut patchbay);
+
+        for (pulse, edge) in edges.iter().enumerate() {
+            assert!((*edge as i32 - pulse as i32 * 48_000).abs() <= 48);
+        }
+
+        for high in highs {
+            assert!((high as i32 - 12_000).abs() <= 48);
+        }
+    }
+
+    #[test]
+    fn clock_should_delay_every_other_pulse_with_swing() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut clock = Clock::new(patchbay.point().unwrap(), 120.0);
+
+        clock.set_pulse_width(0.75).set_swing(0.5);
+        let (edges, highs) = pulses(&mut clock, &mut patchbay);
+
+        let expected = [0, 72_000, 96_000, 168_000];
+
+        for (edge, expected) in edges.iter().zip(expected.iter()) {
+            assert!((*edge as i32 - expected).abs() <= 48);
+        }
+
+        // Swung pulses are shortened by the swing
+        assert!((highs[0] as i32 - 36_000).abs() <= 48);
+        assert!((highs[1] as i32 - 18_000).abs() <= 48);
+    }
+
+    #[test]
+    fn clock_should_derive_divisions_from_the_main_pulse() {
+        let mut patchbay: Patchbay<3> = Patchbay::new();
+        let mut clock = Clock::new(patchbay.point().unwrap(), 120.0);
+
+        assert!(clock.add_division(2, patchbay.point().unwrap()));
+        assert!(clock.add_division(-2, patchbay.point().unwrap()));
+        assert!(matches!(clock.output_division(4), Signal::None));
+
+        let signals = [
+            clock.output(),
+            clock.output_division(2),
+            clock.output_division(-2),
+        ];
+
+        // Rising edges of each output during 8 pulses at 120 BPM
+        let mut edges = [[usize::MAX; 32]; 3];
+        let mut counts = [0; 3];
+        let mut previous = [0.0; 3];
+
+        for i in 0..SAMPLE_RATE * 8 - 1000 {
+            Module::<SAMPLE_RATE>::process(&mut clock, &mut patchbay);
+
+            for output in 0..3 {
+                let value = patchbay.get(signals[output]);
+
+                if value == 1.0 && previous[output] == 0.0 {
+                    edges[output][counts[output]] = i;
+                    counts[output] += 1;
+                }
+
+                previous[output] = value;
+            }
+        }
+
+        assert_eq!(counts, [8, 4, 16]);
+
+        // Divided pulses fire on every second main pulse
+        for i in 0..4 {
+            assert_eq!(edges[1][i], edges[0][i * 2]);
+        }
+
+        // Multiplied pulses fire on and halfway between main pulses
+        for i in 0..8 {
+            assert_eq!(edges[2][i * 2], edges[0][i]);
+            assert!((edges[2][i * 2 + 1] as i32 - edges[0][i] as i32 - 24_000).abs() <= 48);
+        }
     }
 }
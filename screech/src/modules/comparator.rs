@@ -0,0 +1,129 @@
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Compares an input against a threshold and outputs a gate.
+///
+/// By default the output follows the level of the input, with latching enabled the output
+/// toggles state on every rising edge across the threshold instead (T flip-flop).
+pub struct Comparator {
+    input: Signal,
+    threshold: f32,
+    output: PatchPoint,
+    latch: bool,
+    previous_high: bool,
+    state: bool,
+}
+
+impl Comparator {
+    pub fn new(output: PatchPoint) -> Self {
+        Comparator {
+            input: Signal::None,
+            threshold: 0.0,
+            output,
+            latch: false,
+            previous_high: false,
+            state: false,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    pub fn set_threshold(&mut self, threshold: f32) -> &mut Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn get_threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    /// Toggle the output on each rising edge instead of following the input level.
+    pub fn set_latch(&mut self, latch: bool) -> &mut Self {
+        self.latch = latch;
+        self
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Comparator {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let high = patchbay.get(self.input) > self.threshold;
+
+        if self.latch {
+            // Only flip the state on a rising edge
+            if high && !self.previous_high {
+                self.state = !self.state;
+            }
+        } else {
+            self.state = high;
+        }
+
+        self.previous_high = high;
+
+        let output = if self.state { 1.0 } else { 0.0 };
+
+        patchbay.set(&mut self.output, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn comparator_should_follow_the_input_level() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut comparator = Comparator::new(patchbay.point().unwrap());
+
+        comparator.set_input(input.signal()).set_threshold(0.5);
+
+        for (value, expected) in [(0.0, 0.0), (0.6, 1.0), (0.7, 1.0), (0.2, 0.0)] {
+            patchbay.set(&mut input, value);
+            Module::<SAMPLE_RATE>::process(&mut comparator, &mut patchbay);
+            assert_eq!(patchbay.get(comparator.output()), expected);
+        }
+    }
+
+    #[test]
+    fn comparator_should_toggle_on_rising_edges_when_latched() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut comparator = Comparator::new(patchbay.point().unwrap());
+
+        comparator
+            .set_input(input.signal())
+            .set_threshold(0.5)
+            .set_latch(true);
+
+        let steps = [
+            (0.0, 0.0),
+            (1.0, 1.0),
+            (1.0, 1.0),
+            (0.0, 1.0),
+            (1.0, 0.0),
+            (0.0, 0.0),
+            (0.8, 1.0),
+            (0.9, 1.0),
+            (0.1, 1.0),
+            (0.6, 0.0),
+        ];
+
+        for (value, expected) in steps {
+            patchbay.set(&mut input, value);
+            Module::<SAMPLE_RATE>::process(&mut comparator, &mut patchbay);
+            assert_eq!(patchbay.get(comparator.output()), expected);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Convolves the input with an impulse response of up to `N` samples.
+///
+/// The convolution is computed directly in the time domain without adding latency, which keeps
+/// the cost linear in `N` per sample. This works in real time for short to moderate impulse
+/// responses such as small rooms, cabinets or early reflections.
+pub struct ConvReverb<const N: usize> {
+    input: Signal,
+    output: PatchPoint,
+    impulse_response: [f32; N],
+    history: [f32; N],
+    position: usize,
+}
+
+impl<const N: usize> ConvReverb<N> {
+    /// Creates a new reverb with a silent impulse response.
+    pub fn new(output: PatchPoint) -> Self {
+        ConvReverb {
+            input: Signal::None,
+            output,
+            impulse_response: [0.0; N],
+            history: [0.0; N],
+            position: 0,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    /// Set the impulse response, samples beyond `N` are ignored and shorter responses are
+    /// padded with silence.
+    pub fn set_impulse_response(&mut self, impulse_response: &[f32]) -> &mut Self {
+        let length = impulse_response.len().min(N);
+
+        self.impulse_response = [0.0; N];
+        self.impulse_response[..length].copy_from_slice(&impulse_response[..length]);
+        self
+    }
+
+    pub fn get_impulse_response(&self) -> &[f32; N] {
+        &self.impulse_response
+    }
+}
+
+impl<const SAMPLE_RATE: usize, const N: usize> Module<SAMPLE_RATE> for ConvReverb<N> {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        if N == 0 {
+            patchbay.set(&mut self.output, 0.0);
+            return;
+        }
+
+        let position = self.position;
+        self.history[position] = patchbay.get(self.input);
+
+        // Walk backwards through the history ring buffer in two parts to avoid wrapping indices
+        let recent: f32 = self.impulse_response[..=position]
+            .iter()
+            .zip(self.history[..=position].iter().rev())
+            .map(|(h, x)| h * x)
+            .sum();
+
+        let older: f32 = self.impulse_response[position + 1..]
+            .iter()
+            .zip(self.history[position + 1..].iter().rev())
+            .map(|(h, x)| h * x)
+            .sum();
+
+        self.position = (position + 1) % N;
+
+        patchbay.set(&mut self.output, recent + older);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.history = [0.0; N];
+        self.position = 0;
+    }
+
+    fn cost_hint(&self) -> u32 {
+        // A multiply and add for every sample of the impulse response
+        (N as u32).max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn conv_reverb_should_match_a_direct_convolution() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut reverb: ConvReverb<8> = ConvReverb::new(patchbay.point().unwrap());
+        let impulse_response = [1.0, 0.5, -0.25, 0.125, 0.0, 0.3, -0.1];
+
+        reverb
+            .set_input(input.signal())
+            .set_impulse_response(&impulse_response);
+
+        let mut random = Random::new(7);
+        let samples: [f32; 64] = core::array::from_fn(|_| random.next_bipolar());
+
+        for (i, &sample) in samples.iter().enumerate() {
+            patchbay.set(&mut input, sample);
+            Module::<SAMPLE_RATE>::process(&mut reverb, &mut patchbay);
+
+            let expected: f32 = impulse_response
+                .iter()
+                .enumerate()
+                .filter(|&(k, _)| k <= i)
+                .map(|(k, h)| h * samples[i - k])
+                .sum();
+
+            assert!((patchbay.get(reverb.output()) - expected).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn conv_reverb_should_output_the_impulse_response_for_an_impulse() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut reverb: ConvReverb<4> = ConvReverb::new(patchbay.point().unwrap());
+
+        // Longer responses are cut off
+        reverb
+            .set_input(input.signal())
+            .set_impulse_response(&[0.9, 0.6, 0.3, 0.1, 0.05]);
+
+        for (i, expected) in [0.9, 0.6, 0.3, 0.1, 0.0, 0.0].iter().enumerate() {
+            patchbay.set(&mut input, if i == 0 { 1.0 } else { 0.0 });
+            Module::<SAMPLE_RATE>::process(&mut reverb, &mut patchbay);
+            assert_eq!(patchbay.get(reverb.output()), *expected);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use super::biquad::LinkwitzRiley;
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Splits an input into a low and high band using a fourth order Linkwitz-Riley crossover.
+///
+/// Summing the bands gives back the input with a flat magnitude response, only the phase is
+/// shifted around the crossover frequency.
+pub struct Crossover {
+    input: Signal,
+    low: PatchPoint,
+    high: PatchPoint,
+    frequency: f32,
+    split: LinkwitzRiley,
+    update: bool,
+}
+
+impl Crossover {
+    pub fn new(low: PatchPoint, high: PatchPoint) -> Self {
+        Crossover {
+            input: Signal::None,
+            low,
+            high,
+            frequency: 1000.0,
+            split: LinkwitzRiley::new(),
+            update: true,
+        }
+    }
+
+    pub fn low(&self) -> Signal {
+        self.low.signal()
+    }
+
+    pub fn high(&self) -> Signal {
+        self.high.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    /// Set the crossover frequency in Hz, defaults to 1kHz.
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.frequency = frequency;
+        self.update = true;
+        self
+    }
+
+    pub fn get_frequency(&self) -> f32 {
+        self.frequency
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Crossover {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        if self.update {
+            self.split.set_frequency(SAMPLE_RATE, self.frequency);
+            self.update = false;
+        }
+
+        let (low, high) = self.split.process(patchbay.get(self.input));
+
+        patchbay.set(&mut self.low, low);
+        patchbay.set(&mut self.high, high);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.split.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math;
+    use core::f32::consts::TAU;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    // Feed a sine and measure the (low, high, low + high) to input level ratios
+    fn gains(frequency: f32) -> (f32, f32, f32) {
+        let mut patchbay: Patchbay<3> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut crossover = Crossover::new(patchbay.point().unwrap(), patchbay.point().unwrap());
+
+        crossover.set_input(input.signal()).set_frequency(1000.0);
+
+        let mut energy = [0.0; 4];
+
+        for i in 0..SAMPLE_RATE / 2 {
+            let sample = math::sin(TAU * frequency * i as f32 / SAMPLE_RATE as f32);
+
+            patchbay.set(&mut input, sample);
+            Module::<SAMPLE_RATE>::process(&mut crossover, &mut patchbay);
+
+            // Skip the settling time of the filters
+            if i > SAMPLE_RATE / 10 {
+                let low = patchbay.get(crossover.low());
+                let high = patchbay.get(crossover.high());
+
+                energy[0] += sample * sample;
+                energy[1] += low * low;
+                energy[2] += high * high;
+                energy[3] += (low + high) * (low + high);
+            }
+        }
+
+        (
+            math::sqrt(energy[1] / energy[0]),
+            math::sqrt(energy[2] / energy[0]),
+            math::sqrt(energy[3] / energy[0]),
+        )
+    }
+
+    #[test]
+    fn crossover_should_sum_back_to_the_input_level() {
+        for frequency in [50.0, 200.0, 700.0, 1000.0, 1500.0, 5000.0, 15_000.0] {
+            let (_, _, sum) = gains(frequency);
+            assert!((sum - 1.0).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn crossover_should_split_into_bands() {
+        let (low, high, _) = gains(50.0);
+        assert!(low > 0.99 && high < 0.01);
+
+        let (low, high, _) = gains(15_000.0);
+        assert!(low < 0.01 && high > 0.99);
+
+        // Both bands are 6dB down at the crossover frequency
+        let (low, high, _) = gains(1000.0);
+        assert!((low - 0.5).abs() < 0.02 && (high - 0.5).abs() < 0.02);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// One-pole high-pass filter removing DC offset, `y[n] = x[n] - x[n-1] + R * y[n-1]`.
+pub struct DcBlock {
+    input: Signal,
+    output: PatchPoint,
+    coefficient: f32,
+    previous_input: f32,
+    previous_output: f32,
+}
+
+impl DcBlock {
+    pub fn new(output: PatchPoint) -> Self {
+        DcBlock {
+            input: Signal::None,
+            output,
+            coefficient: 0.995,
+            previous_input: 0.0,
+            previous_output: 0.0,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    /// Set the feedback coefficient `R` between 0.0 and 1.0, defaults to 0.995. Values closer
+    /// to 1.0 lower the cutoff and take longer to settle.
+    pub fn set_coefficient(&mut self, coefficient: f32) -> &mut Self {
+        self.coefficient = coefficient.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn get_coefficient(&self) -> f32 {
+        self.coefficient
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for DcBlock {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let input = patchbay.get(self.input);
+        let output = input - self.previous_input + self.coefficient * self.previous_output;
+
+        self.previous_input = input;
+        self.previous_output = output;
+
+        patchbay.set(&mut self.output, output);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.previous_input = 0.0;
+        self.previous_output = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn dc_block_should_decay_a_constant_input_to_zero() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut dc_block = DcBlock::new(patchbay.point().unwrap());
+
+        dc_block.set_input(Signal::Fixed(0.5));
+
+        let mut previous = f32::MAX;
+
+        for i in 0..4800 {
+            Module::<SAMPLE_RATE>::process(&mut dc_block, &mut patchbay);
+            let output = patchbay.get(dc_block.output());
+
+            // The step passes through before decaying
+            if i == 0 {
+                assert_eq!(output, 0.5);
+            }
+
+            assert!(output >= 0.0 && output < previous);
+            previous = output;
+        }
+
+        assert!(previous < 1e-6);
+    }
+}
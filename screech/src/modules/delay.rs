@@ -0,0 +1,220 @@
+use crate::{AntiDenormal, Module, PatchPoint, Patchbay, Signal};
+
+/// Feedback delay line of up to `MAX` samples.
+///
+/// The delay time is read in samples from its [`Signal`] and clamped between 1 and `MAX`, the
+/// feedback sets how much of the delayed signal is fed back into the line. Only the audio input
+/// is required to be ready, so the delay can be used inside feedback loops.
+pub struct Delay<const MAX: usize> {
+    input: Signal,
+    time: Signal,
+    feedback: Signal,
+    output: PatchPoint,
+    wet_output: Option<PatchPoint>,
+    mix: f32,
+    buffer: [f32; MAX],
+    position: usize,
+    anti_denormal: AntiDenormal,
+}
+
+impl<const MAX: usize> Delay<MAX> {
+    pub fn new(output: PatchPoint) -> Self {
+        Delay {
+            input: Signal::None,
+            time: Signal::None,
+            feedback: Signal::None,
+            output,
+            wet_output: None,
+            mix: 0.5,
+            buffer: [0.0; MAX],
+            position: 0,
+            anti_denormal: AntiDenormal::new(),
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    /// Delayed signal only, regardless of the mix, for routing the echoes separately.
+    /// [`Signal::None`] until a point has been set using [`Delay::set_wet_output`].
+    pub fn wet(&self) -> Signal {
+        self.wet_output
+            .as_ref()
+            .map_or(Signal::None, |point| point.signal())
+    }
+
+    pub fn set_wet_output(&mut self, point: PatchPoint) -> &mut Self {
+        self.wet_output = Some(point);
+        self
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    /// Set the signal for the delay time in samples.
+    pub fn set_time(&mut self, signal: Signal) -> &mut Self {
+        self.time = signal;
+        self
+    }
+
+    pub fn set_feedback(&mut self, signal: Signal) -> &mut Self {
+        self.feedback = signal;
+        self
+    }
+
+    /// Set the balance between the dry (0.0) and delayed (1.0) signal.
+    pub fn set_mix(&mut self, mix: f32) -> &mut Self {
+        self.mix = mix.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn get_mix(&self) -> f32 {
+        self.mix
+    }
+
+    /// Flush denormal values from the feedback path, see [`AntiDenormal`].
+    pub fn set_anti_denormal(&mut self, enabled: bool) -> &mut Self {
+        self.anti_denormal.set_enabled(enabled);
+        self
+    }
+}
+
+impl<const SAMPLE_RATE: usize, const MAX: usize> Module<SAMPLE_RATE> for Delay<MAX> {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let input = patchbay.get(self.input);
+
+        if MAX == 0 {
+            patchbay.set(&mut self.output, input * (1.0 - self.mix));
+
+            if let Some(point) = self.wet_output.as_mut() {
+                patchbay.set(point, 0.0);
+            }
+
+            return;
+        }
+
+        let time = (patchbay.get(self.time) as usize).clamp(1, MAX);
+        let feedback = patchbay.get(self.feedback);
+
+        // A time of `MAX` reads the oldest sample right before it gets overwritten
+        let delayed = self.buffer[(self.position + MAX - time) % MAX];
+
+        self.buffer[self.position] = self.anti_denormal.process(input + delayed * feedback);
+        self.position = (self.position + 1) % MAX;
+
+        let output = input * (1.0 - self.mix) + delayed * self.mix;
+
+        patchbay.set(&mut self.output, output);
+
+        if let Some(point) = self.wet_output.as_mut() {
+            patchbay.set(point, delayed);
+        }
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.buffer = [0.0; MAX];
+        self.position = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    fn impulse_response<const N: usize>(time: f32, feedback: f32) -> [f32; N] {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut delay: Delay<16> = Delay::new(patchbay.point().unwrap());
+        let mut output = [0.0; N];
+
+        delay
+            .set_input(input.signal())
+            .set_time(Signal::Fixed(time))
+            .set_feedback(Signal::Fixed(feedback))
+            .set_mix(1.0);
+
+        for (i, sample) in output.iter_mut().enumerate() {
+            patchbay.set(&mut input, if i == 0 { 1.0 } else { 0.0 });
+            Module::<SAMPLE_RATE>::process(&mut delay, &mut patchbay);
+            *sample = patchbay.get(delay.output());
+        }
+
+        output
+    }
+
+    #[test]
+    fn delay_should_repeat_an_impulse_after_the_delay_time() {
+        let output = impulse_response::<32>(5.0, 0.0);
+
+        for (i, &sample) in output.iter().enumerate() {
+            assert_eq!(sample, if i == 5 { 1.0 } else { 0.0 });
+        }
+
+        // The full length of the buffer
+        let output = impulse_response::<32>(16.0, 0.0);
+        assert_eq!(output[16], 1.0);
+    }
+
+    #[test]
+    fn delay_should_feed_back_repeats() {
+        let output = impulse_response::<16>(4.0, 0.5);
+
+        assert_eq!(output[4], 1.0);
+        assert_eq!(output[8], 0.5);
+        assert_eq!(output[12], 0.25);
+        assert_eq!(output.iter().filter(|&&s| s != 0.0).count(), 3);
+    }
+
+    #[test]
+    fn delay_should_mix_the_dry_signal() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut delay: Delay<4> = Delay::new(patchbay.point().unwrap());
+
+        delay.set_input(Signal::Fixed(1.0)).set_mix(0.25);
+
+        Module::<SAMPLE_RATE>::process(&mut delay, &mut patchbay);
+        assert_eq!(patchbay.get(delay.output()), 0.75);
+
+        Module::<SAMPLE_RATE>::process(&mut delay, &mut patchbay);
+        assert_eq!(patchbay.get(delay.output()), 1.0);
+    }
+
+    #[test]
+    fn delay_should_output_only_echoes_on_the_wet_output() {
+        let mut patchbay: Patchbay<3> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut delay: Delay<16> = Delay::new(patchbay.point().unwrap());
+
+        delay
+            .set_wet_output(patchbay.point().unwrap())
+            .set_input(input.signal())
+            .set_time(Signal::Fixed(4.0))
+            .set_feedback(Signal::Fixed(0.5));
+
+        let mut mixed = [0.0; 12];
+        let mut wet = [0.0; 12];
+
+        for i in 0..12 {
+            patchbay.set(&mut input, if i == 0 { 1.0 } else { 0.0 });
+            Module::<SAMPLE_RATE>::process(&mut delay, &mut patchbay);
+            mixed[i] = patchbay.get(delay.output());
+            wet[i] = patchbay.get(delay.wet());
+        }
+
+        // The mixed output starts with the dry impulse, the wet output with silence
+        assert_eq!(mixed[0], 0.5);
+        assert_eq!(
+            wet,
+            [0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use crate::random::Random;
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Bounded random walk, every rising clock edge steps the output up or down.
+///
+/// Steps that would leave the range are reflected back off the bound. The output starts in
+/// the middle of the range, use [`Drunk::set_seed`] for reproducible walks.
+pub struct Drunk {
+    clock: Signal,
+    output: PatchPoint,
+    step: f32,
+    min: f32,
+    max: f32,
+    value: f32,
+    previous_clock: f32,
+    random: Random,
+}
+
+impl Drunk {
+    pub fn new(output: PatchPoint) -> Self {
+        Drunk {
+            clock: Signal::None,
+            output,
+            step: 0.1,
+            min: -1.0,
+            max: 1.0,
+            value: 0.0,
+            previous_clock: 0.0,
+            random: Random::new(1),
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_clock(&mut self, signal: Signal) -> &mut Self {
+        self.clock = signal;
+        self
+    }
+
+    /// Size of a single step, defaults to 0.1.
+    pub fn set_step(&mut self, step: f32) -> &mut Self {
+        self.step = step.abs();
+        self
+    }
+
+    pub fn get_step(&self) -> f32 {
+        self.step
+    }
+
+    /// Set the bounds of the walk and move the output to the middle of the new range, defaults
+    /// to -1.0 to 1.0.
+    pub fn set_range(&mut self, min: f32, max: f32) -> &mut Self {
+        self.min = min.min(max);
+        self.max = max.max(min);
+        self.value = (self.min + self.max) * 0.5;
+        self
+    }
+
+    pub fn get_range(&self) -> (f32, f32) {
+        (self.min, self.max)
+    }
+
+    pub fn set_seed(&mut self, seed: u32) -> &mut Self {
+        self.random = Random::new(seed);
+        self
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Drunk {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.clock)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let clock = patchbay.get(self.clock);
+
+        if clock >= 0.5 && self.previous_clock < 0.5 {
+            let step = if self.random.next_u32() & 1 == 1 {
+                self.step
+            } else {
+                -self.step
+            };
+
+            self.value += step;
+
+            // Reflect off the bounds
+            if self.value > self.max {
+                self.value = self.max - (self.value - self.max);
+            } else if self.value < self.min {
+                self.value = self.min + (self.min - self.value);
+            }
+
+            // Steps larger than the range could still overshoot
+            self.value = self.value.clamp(self.min, self.max);
+        }
+
+        self.previous_clock = clock;
+
+        patchbay.set(&mut self.output, self.value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    fn walk(seed: u32) -> [f32; 200] {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut clock = patchbay.point().unwrap();
+        let mut drunk = Drunk::new(patchbay.point().unwrap());
+        let mut output = [0.0; 200];
+
+        drunk
+            .set_clock(clock.signal())
+            .set_step(0.25)
+            .set_range(0.0, 1.0)
+            .set_seed(seed);
+
+        for value in output.iter_mut() {
+            for i in 0..2 {
+                patchbay.set(&mut clock, if i == 0 { 1.0 } else { 0.0 });
+                Module::<SAMPLE_RATE>::process(&mut drunk, &mut patchbay);
+            }
+
+            *value = patchbay.get(drunk.output());
+        }
+
+        output
+    }
+
+    #[test]
+    fn drunk_should_walk_within_the_range() {
+        let output = walk(7);
+        let mut previous = 0.5;
+
+        for value in output.iter() {
+            assert!((0.0..=1.0).contains(value));
+            assert_eq!((value - previous).abs(), 0.25);
+            previous = *value;
+        }
+
+        // Reaching both bounds along the way
+        assert!(output.contains(&0.0));
+        assert!(output.contains(&1.0));
+    }
+
+    #[test]
+    fn drunk_should_be_deterministic_for_a_seed() {
+        assert_eq!(walk(7), walk(7));
+        assert_ne!(walk(7), walk(8));
+    }
+}
@@ -1,18 +1,76 @@
-use crate::{Module, PatchPoint, Patchbay, Signal};
+use crate::math;
+use crate::{Event, Module, PatchPoint, Patchbay, Signal};
+
+// How far a fully exponential segment bends away from a straight line
+const CURVE_STEEPNESS: f32 = 5.0;
+
+// Release time in seconds of a choked envelope
+const CHOKE_TIME: f32 = 0.002;
+
+// Maximum amount of entries in a stage shape table
+const SHAPE_SIZE: usize = 32;
+
+// User supplied contour of a stage, read with linear interpolation
+#[derive(Copy, Clone)]
+struct ShapeTable {
+    table: [f32; SHAPE_SIZE],
+    length: usize,
+}
+
+impl ShapeTable {
+    // Copy up to `SHAPE_SIZE` entries, returns `None` for an empty table
+    fn new(table: &[f32]) -> Option<Self> {
+        let length = table.len().min(SHAPE_SIZE);
+        let mut shape = ShapeTable {
+            table: [0.0; SHAPE_SIZE],
+            length,
+        };
+
+        shape.table[..length].copy_from_slice(&table[..length]);
+
+        if length > 0 {
+            Some(shape)
+        } else {
+            None
+        }
+    }
+
+    fn lookup(&self, progress: f32) -> f32 {
+        if self.length < 2 {
+            return self.table[0];
+        }
+
+        let position = progress.clamp(0.0, 1.0) * (self.length - 1) as f32;
+        let index = (position as usize).min(self.length - 2);
+        let fraction = position - index as f32;
+
+        self.table[index] + (self.table[index + 1] - self.table[index]) * fraction
+    }
+}
 
 enum Curve {
-    AR(f32, f32),
-    ADSR(f32, f32, f32, f32),
+    Ar(f32, f32),
+    Adsr(f32, f32, f32, f32),
 }
 
 pub struct Envelope {
     output: PatchPoint,
     trigger: Signal,
     previous_trigger: f32,
+    held: bool,
+    choked: bool,
     value: f32,
     curve: Curve,
     is_active: bool,
     active_stage: usize,
+    stage_start: f32,
+    progress: f32,
+    attack_curve: f32,
+    decay_curve: f32,
+    release_curve: f32,
+    attack_shape: Option<ShapeTable>,
+    decay_shape: Option<ShapeTable>,
+    release_shape: Option<ShapeTable>,
 }
 
 impl Envelope {
@@ -21,10 +79,20 @@ impl Envelope {
             output,
             trigger,
             previous_trigger: 0.0,
+            held: false,
+            choked: false,
             value: 0.0,
-            curve: Curve::AR(0.1, 0.1),
+            curve: Curve::Ar(0.1, 0.1),
             is_active: false,
             active_stage: 0,
+            stage_start: 0.0,
+            progress: 0.0,
+            attack_curve: 0.0,
+            decay_curve: 0.0,
+            release_curve: 0.0,
+            attack_shape: None,
+            decay_shape: None,
+            release_shape: None,
         }
     }
 
@@ -33,60 +101,175 @@ impl Envelope {
     }
 
     pub fn set_ar(&mut self, a: f32, r: f32) -> &mut Self {
-        self.curve = Curve::AR(a, r);
+        self.curve = Curve::Ar(a, r);
         self
     }
 
+    /// Set the attack, decay and release times in seconds and the sustain level, the sustain
+    /// level is held for as long as the trigger stays high.
     pub fn set_adsr(&mut self, a: f32, d: f32, s: f32, r: f32) -> &mut Self {
-        self.curve = Curve::ADSR(a, d, s, r);
+        self.curve = Curve::Adsr(a, d, s, r);
+        self
+    }
+
+    /// Bend the attack between linear (0.0) and exponential (1.0), exponential rises quickly
+    /// at first and slows down towards the peak.
+    pub fn set_attack_curve(&mut self, curve: f32) -> &mut Self {
+        self.attack_curve = curve.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn get_attack_curve(&self) -> f32 {
+        self.attack_curve
+    }
+
+    /// Bend the decay of an ADSR envelope between linear (0.0) and exponential (1.0).
+    pub fn set_decay_curve(&mut self, curve: f32) -> &mut Self {
+        self.decay_curve = curve.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn get_decay_curve(&self) -> f32 {
+        self.decay_curve
+    }
+
+    /// Bend the release between linear (0.0) and exponential (1.0), exponential falls quickly
+    /// at first and slows down towards zero.
+    pub fn set_release_curve(&mut self, curve: f32) -> &mut Self {
+        self.release_curve = curve.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn get_release_curve(&self) -> f32 {
+        self.release_curve
+    }
+
+    /// Follow a table for the attack instead of the attack curve. The entries are spread evenly
+    /// across the stage and run from 0.0 at the start to 1.0 at the peak, values in between are
+    /// interpolated. Up to 32 entries are used, an empty table goes back to the curve.
+    pub fn set_attack_shape(&mut self, table: &[f32]) -> &mut Self {
+        self.attack_shape = ShapeTable::new(table);
+        self
+    }
+
+    /// Follow a table for the decay of an ADSR envelope, see [`Envelope::set_attack_shape`].
+    pub fn set_decay_shape(&mut self, table: &[f32]) -> &mut Self {
+        self.decay_shape = ShapeTable::new(table);
+        self
+    }
+
+    /// Follow a table for the release, see [`Envelope::set_attack_shape`].
+    pub fn set_release_shape(&mut self, table: &[f32]) -> &mut Self {
+        self.release_shape = ShapeTable::new(table);
         self
     }
 
-    pub fn process_curve<const SAMPLE_RATE: usize>(&mut self) {
+    /// Whether the envelope is still running, inactive envelopes output silence.
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    /// Index of the running stage: attack, release for AR or attack, decay, sustain, release
+    /// for ADSR envelopes.
+    pub fn current_stage(&self) -> usize {
+        self.active_stage
+    }
+
+    fn start_stage(&mut self, stage: usize) {
+        self.active_stage = stage;
+        self.stage_start = self.value;
+        self.progress = 0.0;
+    }
+
+    // Index of the release stage of the curve
+    fn release_stage(&self) -> usize {
+        match self.curve {
+            Curve::Ar(_, _) => 1,
+            Curve::Adsr(_, _, _, _) => 3,
+        }
+    }
+
+    /// Advance the envelope by one sample, `gate` tells an ADSR envelope whether to hold the
+    /// sustain level or to release.
+    pub fn process_curve<const SAMPLE_RATE: usize>(&mut self, gate: bool) {
         let seconds_per_sample = 1.0 / SAMPLE_RATE as f32;
 
         match self.curve {
-            Curve::AR(a, r) => match self.active_stage {
+            Curve::Ar(a, r) => match self.active_stage {
                 0 => {
-                    self.value += seconds_per_sample / a;
+                    self.progress = (self.progress + seconds_per_sample / a).min(1.0);
+                    self.value = self.stage_start
+                        + (1.0 - self.stage_start)
+                            * stage_shape(self.progress, self.attack_curve, &self.attack_shape);
 
-                    if self.value >= 1.0 {
-                        self.active_stage += 1;
+                    if self.progress >= 1.0 {
+                        self.start_stage(1);
                     }
                 }
                 1 => {
-                    self.value -= seconds_per_sample / r;
-                    if self.value <= 0.0 {
-                        self.active_stage += 1;
+                    let r = if self.choked { CHOKE_TIME } else { r };
+                    self.progress = (self.progress + seconds_per_sample / r).min(1.0);
+                    self.value = self.stage_start
+                        * (1.0
+                            - stage_shape(self.progress, self.release_curve, &self.release_shape));
+
+                    if self.progress >= 1.0 {
+                        self.start_stage(2);
                     }
                 }
                 _ => self.is_active = false,
             },
-            Curve::ADSR(a, d, s, r) => match self.active_stage {
-                0 => {
-                    self.value += self.value * a;
-                    if self.value >= 1.0 {
-                        self.active_stage += 1;
-                    }
+            Curve::Adsr(a, d, s, r) => {
+                // Release from wherever the envelope is as soon as the gate drops
+                if !gate && self.active_stage < 3 {
+                    self.start_stage(3);
                 }
-                1 => {
-                    self.value -= self.value * d;
-                    if self.value <= s {
-                        self.active_stage += 1;
+
+                match self.active_stage {
+                    0 => {
+                        self.progress = (self.progress + seconds_per_sample / a).min(1.0);
+                        self.value = self.stage_start
+                            + (1.0 - self.stage_start)
+                                * stage_shape(self.progress, self.attack_curve, &self.attack_shape);
+
+                        if self.progress >= 1.0 {
+                            self.start_stage(1);
+                        }
                     }
-                }
-                2 => {
-                    // @TODO:
-                    self.active_stage += 1;
-                }
-                3 => {
-                    self.value -= self.value * r;
-                    if self.value <= 0.0 {
-                        self.active_stage += 1;
+                    1 => {
+                        self.progress = (self.progress + seconds_per_sample / d).min(1.0);
+                        self.value = s
+                            + (self.stage_start - s)
+                                * (1.0
+                                    - stage_shape(
+                                        self.progress,
+                                        self.decay_curve,
+                                        &self.decay_shape,
+                                    ));
+
+                        if self.progress >= 1.0 {
+                            self.start_stage(2);
+                        }
+                    }
+                    2 => self.value = s,
+                    3 => {
+                        let r = if self.choked { CHOKE_TIME } else { r };
+                        self.progress = (self.progress + seconds_per_sample / r).min(1.0);
+                        self.value = self.stage_start
+                            * (1.0
+                                - stage_shape(
+                                    self.progress,
+                                    self.release_curve,
+                                    &self.release_shape,
+                                ));
+
+                        if self.progress >= 1.0 {
+                            self.start_stage(4);
+                        }
                     }
+                    _ => self.is_active = false,
                 }
-                _ => self.is_active = false,
-            },
+            }
         }
     }
 }
@@ -99,24 +282,29 @@ impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Envelope {
     fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
         let trigger = patchbay.get(self.trigger);
         let triggered = trigger >= 0.5 && self.previous_trigger < 0.5;
+        let gate = trigger >= 0.5 || self.held;
+
+        if triggered {
+            self.choked = false;
+        }
 
         let output = match (self.is_active, triggered) {
             // Active, but retriggered -> restart envelope
             (true, true) => {
-                self.active_stage = 0;
-                self.process_curve::<P>();
+                self.start_stage(0);
+                self.process_curve::<SAMPLE_RATE>(gate);
                 self.value
             }
             // Inactive, triggered -> start envelope
             (false, true) => {
                 // Trigger is in the active region -> activate
                 self.is_active = true;
-                self.active_stage = 0;
+                self.start_stage(0);
                 0.0
             }
             // Active, no trigger -> Continue processing the envelope curve
             (true, false) => {
-                self.process_curve::<P>();
+                self.process_curve::<SAMPLE_RATE>(gate);
                 self.value
             }
             // Inactive, no trigger -> no output
@@ -127,4 +315,294 @@ impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Envelope {
 
         self.previous_trigger = trigger;
     }
+
+    /// A note on starts the envelope, holding an ADSR envelope at its sustain level until the
+    /// note off. A choke releases the envelope within a couple of milliseconds.
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::NoteOn { .. } => {
+                self.held = true;
+                self.choked = false;
+                self.is_active = true;
+                self.start_stage(0);
+            }
+            Event::NoteOff { .. } => self.held = false,
+            Event::Choke => {
+                self.held = false;
+
+                if self.is_active && self.active_stage <= self.release_stage() {
+                    self.choked = true;
+                    self.start_stage(self.release_stage());
+                }
+            }
+        }
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.previous_trigger = 0.0;
+        self.held = false;
+        self.choked = false;
+        self.value = 0.0;
+        self.is_active = false;
+        self.start_stage(0);
+    }
+}
+
+// Shape the progress through a stage using its table when set, otherwise its curve
+fn stage_shape(progress: f32, curve: f32, table: &Option<ShapeTable>) -> f32 {
+    match table {
+        Some(table) => table.lookup(progress),
+        None => shape(progress, curve),
+    }
+}
+
+// Map the linear progress through a segment onto a curve that is bent between linear (0.0) and
+// exponential (1.0), like a capacitor charging towards its target
+fn shape(progress: f32, curve: f32) -> f32 {
+    if curve <= 0.0 {
+        return progress;
+    }
+
+    let exponential =
+        (1.0 - math::exp(-CURVE_STEEPNESS * progress)) / (1.0 - math::exp(-CURVE_STEEPNESS));
+
+    progress + (exponential - progress) * curve
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventQueue, Processor};
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    fn attack(curve: f32) -> [f32; 100] {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut trigger = patchbay.point().unwrap();
+        let mut envelope = Envelope::new(trigger.signal(), patchbay.point().unwrap());
+        let mut output = [0.0; 100];
+
+        // 100 samples of attack
+        envelope
+            .set_ar(100.0 / SAMPLE_RATE as f32, 0.1)
+            .set_attack_curve(curve);
+
+        patchbay.set(&mut trigger, 1.0);
+        Module::<SAMPLE_RATE>::process(&mut envelope, &mut patchbay);
+
+        for sample in output.iter_mut() {
+            Module::<SAMPLE_RATE>::process(&mut envelope, &mut patchbay);
+            *sample = patchbay.get(envelope.output());
+        }
+
+        output
+    }
+
+    #[test]
+    fn envelope_should_rise_linearly_without_a_curve() {
+        let output = attack(0.0);
+
+        assert!((output[24] - 0.25).abs() < 1e-4);
+        assert!((output[49] - 0.5).abs() < 1e-4);
+        assert!((output[99] - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn envelope_should_front_load_an_exponential_attack() {
+        let linear = attack(0.0);
+        let curved = attack(1.0);
+
+        assert!(curved[24] > linear[24] + 0.2);
+        assert!(curved[49] > linear[49] + 0.2);
+        assert!((curved[99] - 1.0).abs() < 1e-4);
+
+        // Still rising all the way up to the peak
+        for pair in curved.windows(2) {
+            assert!(pair[1] > pair[0]);
+        }
+    }
+
+    #[test]
+    fn envelope_should_curve_the_release_independently() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut envelope = Envelope::new(Signal::Fixed(1.0), patchbay.point().unwrap());
+        let release = 100.0 / SAMPLE_RATE as f32;
+
+        envelope.set_ar(release, release).set_release_curve(1.0);
+
+        // Trigger and attack
+        for _ in 0..101 {
+            Module::<SAMPLE_RATE>::process(&mut envelope, &mut patchbay);
+        }
+
+        assert!((patchbay.get(envelope.output()) - 1.0).abs() < 1e-4);
+
+        // Halfway through the release an exponential curve has dropped well past half
+        for _ in 0..50 {
+            Module::<SAMPLE_RATE>::process(&mut envelope, &mut patchbay);
+        }
+
+        assert!(patchbay.get(envelope.output()) < 0.3);
+    }
+
+    // Run an ADSR envelope with a gate held high for `gate` samples
+    fn adsr(gate: usize) -> [f32; 400] {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut trigger = patchbay.point().unwrap();
+        let mut envelope = Envelope::new(trigger.signal(), patchbay.point().unwrap());
+        let mut output = [0.0; 400];
+        let time = 50.0 / SAMPLE_RATE as f32;
+
+        // 50 samples for each stage
+        envelope.set_adsr(time, time, 0.5, time);
+
+        for (i, sample) in output.iter_mut().enumerate() {
+            patchbay.set(&mut trigger, if i < gate { 1.0 } else { 0.0 });
+            Module::<SAMPLE_RATE>::process(&mut envelope, &mut patchbay);
+            *sample = patchbay.get(envelope.output());
+        }
+
+        output
+    }
+
+    #[test]
+    fn envelope_should_hold_the_sustain_level_while_gated() {
+        let output = adsr(300);
+
+        // Stage boundaries can shift by a sample due to rounding, one sample is a 0.01 step
+        assert!((output[25] - 0.5).abs() < 0.011);
+        assert!((output[50] - 1.0).abs() < 0.011);
+        assert!((output[75] - 0.75).abs() < 0.011);
+
+        for sample in output[102..300].iter() {
+            assert_eq!(*sample, 0.5);
+        }
+
+        // Released once the gate drops
+        assert!((output[325] - 0.25).abs() < 0.011);
+        assert_eq!(output[352], 0.0);
+        assert_eq!(output[399], 0.0);
+    }
+
+    #[test]
+    fn envelope_should_release_early_on_a_short_trigger() {
+        let output = adsr(10);
+
+        // Releasing from the level reached during the attack
+        let peak = output[9];
+        assert!((peak - 0.18).abs() < 1e-4);
+
+        for pair in output[10..59].windows(2) {
+            assert!(pair[1] < pair[0]);
+        }
+
+        assert_eq!(output[62], 0.0);
+        assert!(output.iter().all(|s| *s <= peak));
+    }
+
+    #[test]
+    fn envelope_should_report_its_stage_and_activity() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut envelope = Envelope::new(Signal::Fixed(1.0), patchbay.point().unwrap());
+        let time = 10.0 / SAMPLE_RATE as f32;
+
+        envelope.set_ar(time, time);
+        assert!(!envelope.is_active());
+
+        Module::<SAMPLE_RATE>::process(&mut envelope, &mut patchbay);
+        assert!(envelope.is_active());
+        assert_eq!(envelope.current_stage(), 0);
+
+        for _ in 0..12 {
+            Module::<SAMPLE_RATE>::process(&mut envelope, &mut patchbay);
+        }
+
+        assert!(envelope.is_active());
+        assert_eq!(envelope.current_stage(), 1);
+
+        for _ in 0..12 {
+            Module::<SAMPLE_RATE>::process(&mut envelope, &mut patchbay);
+        }
+
+        assert!(!envelope.is_active());
+    }
+
+    #[test]
+    fn envelope_should_follow_an_attack_shape_table() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut trigger = patchbay.point().unwrap();
+        let mut envelope = Envelope::new(trigger.signal(), patchbay.point().unwrap());
+
+        // S-curve with a raised midpoint, 100 samples of attack
+        envelope
+            .set_ar(100.0 / SAMPLE_RATE as f32, 0.1)
+            .set_attack_shape(&[0.0, 0.1, 0.7, 0.9, 1.0]);
+
+        patchbay.set(&mut trigger, 1.0);
+        Module::<SAMPLE_RATE>::process(&mut envelope, &mut patchbay);
+
+        let mut output = [0.0; 100];
+
+        for sample in output.iter_mut() {
+            Module::<SAMPLE_RATE>::process(&mut envelope, &mut patchbay);
+            *sample = patchbay.get(envelope.output());
+        }
+
+        assert!((output[24] - 0.1).abs() < 1e-3);
+        assert!((output[49] - 0.7).abs() < 1e-3);
+        assert!((output[74] - 0.9).abs() < 1e-3);
+        assert!((output[99] - 1.0).abs() < 1e-3);
+
+        // Interpolated between entries
+        assert!((output[36] - 0.4).abs() < 0.03);
+    }
+
+    #[test]
+    fn envelope_should_be_choked_by_another_envelope_in_its_group() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut events: EventQueue<4> = EventQueue::new();
+        let envelopes: [Envelope; 2] = core::array::from_fn(|_| {
+            let mut envelope = Envelope::new(Signal::None, patchbay.point().unwrap());
+            envelope.set_adsr(0.001, 0.1, 0.8, 1.0);
+            envelope
+        });
+        let outputs = [envelopes[0].output(), envelopes[1].output()];
+        let mut processor: Processor<SAMPLE_RATE, 2, Envelope> =
+            Processor::new(envelopes.map(Some));
+
+        processor.set_choke_group(0, Some(1));
+        processor.set_choke_group(1, Some(1));
+
+        let note = |note| Event::NoteOn {
+            note,
+            velocity: 1.0,
+        };
+
+        events.push(0, 0, note(42));
+
+        for _ in 0..1000 {
+            processor.process_modules_with_events(&mut patchbay, &mut events);
+        }
+
+        // Held past the attack and decay by the note
+        assert!(patchbay.get(outputs[0]) > 0.5);
+
+        events.push(0, 1, note(46));
+
+        for _ in 0..200 {
+            processor.process_modules_with_events(&mut patchbay, &mut events);
+        }
+
+        // Cut off within a few milliseconds
+        assert_eq!(patchbay.get(outputs[0]), 0.0);
+        assert!(!processor.get_module(0).unwrap().is_active());
+        assert!(patchbay.get(outputs[1]) > 0.5);
+
+        // A note off releases as usual
+        events.push(0, 1, Event::NoteOff { note: 46 });
+        processor.process_modules_with_events(&mut patchbay, &mut events);
+
+        assert_eq!(processor.get_module(1).unwrap().current_stage(), 3);
+        assert!(patchbay.get(outputs[1]) > 0.5);
+    }
 }
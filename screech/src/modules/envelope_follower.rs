@@ -0,0 +1,172 @@
+use crate::math;
+use crate::{Module, PatchPoint, Patchbay, Signal};
+use core::f32::consts::TAU;
+
+// Cutoff of the DC blocker in Hz, well below the audible range
+const DC_BLOCK_FREQUENCY: f32 = 10.0;
+
+/// Follows the level of the rectified input with separate attack and release times.
+///
+/// By default the input is AC coupled, a DC blocker removes any offset before rectifying so
+/// only the amplitude of the signal itself is measured. See [`EnvelopeFollower::set_ac_coupled`].
+pub struct EnvelopeFollower {
+    input: Signal,
+    output: PatchPoint,
+    attack: f32,
+    release: f32,
+    ac_coupled: bool,
+    attack_coefficient: f32,
+    release_coefficient: f32,
+    dc_coefficient: f32,
+    previous_input: f32,
+    blocked: f32,
+    level: f32,
+    update: bool,
+}
+
+impl EnvelopeFollower {
+    pub fn new(output: PatchPoint) -> Self {
+        EnvelopeFollower {
+            input: Signal::None,
+            output,
+            attack: 0.001,
+            release: 0.1,
+            ac_coupled: true,
+            attack_coefficient: 1.0,
+            release_coefficient: 1.0,
+            dc_coefficient: 0.0,
+            previous_input: 0.0,
+            blocked: 0.0,
+            level: 0.0,
+            update: true,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    /// Time in seconds to rise towards a louder input, defaults to 1ms.
+    pub fn set_attack(&mut self, seconds: f32) -> &mut Self {
+        self.attack = seconds;
+        self.update = true;
+        self
+    }
+
+    pub fn get_attack(&self) -> f32 {
+        self.attack
+    }
+
+    /// Time in seconds to fall towards a quieter input, defaults to 100ms.
+    pub fn set_release(&mut self, seconds: f32) -> &mut Self {
+        self.release = seconds;
+        self.update = true;
+        self
+    }
+
+    pub fn get_release(&self) -> f32 {
+        self.release
+    }
+
+    /// Remove DC offset from the input before measuring, enabled by default.
+    pub fn set_ac_coupled(&mut self, ac_coupled: bool) -> &mut Self {
+        self.ac_coupled = ac_coupled;
+        self
+    }
+
+    pub fn get_ac_coupled(&self) -> bool {
+        self.ac_coupled
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for EnvelopeFollower {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        if self.update {
+            self.attack_coefficient = math::one_pole_coefficient(self.attack, SAMPLE_RATE);
+            self.release_coefficient = math::one_pole_coefficient(self.release, SAMPLE_RATE);
+            self.dc_coefficient = math::exp(-TAU * DC_BLOCK_FREQUENCY / SAMPLE_RATE as f32);
+            self.update = false;
+        }
+
+        let input = patchbay.get(self.input);
+
+        // Keep the DC blocker running so switching coupling doesn't cause a jump
+        self.blocked = input - self.previous_input + self.dc_coefficient * self.blocked;
+        self.previous_input = input;
+
+        let rectified = if self.ac_coupled {
+            self.blocked.abs()
+        } else {
+            input.abs()
+        };
+
+        let coefficient = if rectified > self.level {
+            self.attack_coefficient
+        } else {
+            self.release_coefficient
+        };
+
+        self.level += (rectified - self.level) * coefficient;
+
+        patchbay.set(&mut self.output, self.level);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.previous_input = 0.0;
+        self.blocked = 0.0;
+        self.level = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    // Highest level measured during the last 100ms of a second of a 100Hz sine with an
+    // amplitude of 0.5 on top of a 0.3 offset
+    fn level(ac_coupled: bool) -> f32 {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut follower = EnvelopeFollower::new(patchbay.point().unwrap());
+        let mut level: f32 = 0.0;
+
+        follower
+            .set_input(input.signal())
+            .set_ac_coupled(ac_coupled);
+
+        for i in 0..SAMPLE_RATE {
+            let sample = 0.3 + 0.5 * math::sin(TAU * 100.0 * i as f32 / SAMPLE_RATE as f32);
+
+            patchbay.set(&mut input, sample);
+            Module::<SAMPLE_RATE>::process(&mut follower, &mut patchbay);
+
+            if i > SAMPLE_RATE / 10 * 9 {
+                level = level.max(patchbay.get(follower.output()));
+            }
+        }
+
+        level
+    }
+
+    #[test]
+    fn envelope_follower_should_measure_the_ac_amplitude() {
+        assert!((level(true) - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn envelope_follower_should_include_the_offset_when_dc_coupled() {
+        // Biased towards the 0.8 peak of the offset signal
+        assert!(level(false) > 0.75);
+    }
+}
@@ -0,0 +1,241 @@
+use crate::math;
+use crate::{Module, PatchPoint, Patchbay, Signal};
+use core::f32::consts::PI;
+
+enum Mode {
+    Low,
+    High,
+    Band,
+}
+
+/// Resonant state variable filter with low pass, high pass and band pass modes.
+///
+/// The cutoff is read in Hz and the resonance between 0.0 and 1.0 from their [`Signal`]s every
+/// sample, so both can be modulated at audio rate. Uses the trapezoidal integrated topology
+/// which stays stable under fast modulation.
+pub struct Filter {
+    input: Signal,
+    cutoff: Signal,
+    resonance: Signal,
+    output: PatchPoint,
+    mode: Mode,
+    previous_cutoff: f32,
+    previous_resonance: f32,
+    g: f32,
+    k: f32,
+    ic1eq: f32,
+    ic2eq: f32,
+}
+
+impl Filter {
+    pub fn new(output: PatchPoint) -> Self {
+        Filter {
+            input: Signal::None,
+            cutoff: Signal::None,
+            resonance: Signal::None,
+            output,
+            mode: Mode::Low,
+            previous_cutoff: f32::NAN,
+            previous_resonance: f32::NAN,
+            g: 0.0,
+            k: 2.0,
+            ic1eq: 0.0,
+            ic2eq: 0.0,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    /// Set the signal for the cutoff frequency in Hz.
+    pub fn set_cutoff(&mut self, signal: Signal) -> &mut Self {
+        self.cutoff = signal;
+        self
+    }
+
+    /// Set the signal for the resonance between 0.0 and 1.0, self oscillating near 1.0.
+    pub fn set_resonance(&mut self, signal: Signal) -> &mut Self {
+        self.resonance = signal;
+        self
+    }
+
+    pub fn low_pass(&mut self) -> &mut Self {
+        self.mode = Mode::Low;
+        self
+    }
+
+    pub fn high_pass(&mut self) -> &mut Self {
+        self.mode = Mode::High;
+        self
+    }
+
+    pub fn band_pass(&mut self) -> &mut Self {
+        self.mode = Mode::Band;
+        self
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Filter {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input) && patchbay.check(self.cutoff) && patchbay.check(self.resonance)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let cutoff = patchbay.get(self.cutoff);
+        let resonance = patchbay.get(self.resonance);
+
+        // Only recalculate the coefficients when the cutoff or resonance moved
+        if cutoff != self.previous_cutoff || resonance != self.previous_resonance {
+            let nyquist = SAMPLE_RATE as f32 * 0.5;
+            let frequency = cutoff.clamp(1.0, nyquist * 0.99);
+
+            self.g = math::tan(PI * frequency / SAMPLE_RATE as f32);
+            self.k = 2.0 - 2.0 * resonance.clamp(0.0, 0.99);
+            self.previous_cutoff = cutoff;
+            self.previous_resonance = resonance;
+        }
+
+        let a1 = 1.0 / (1.0 + self.g * (self.g + self.k));
+        let a2 = self.g * a1;
+        let a3 = self.g * a2;
+
+        let input = patchbay.get(self.input);
+        let v3 = input - self.ic2eq;
+        let v1 = a1 * self.ic1eq + a2 * v3;
+        let v2 = self.ic2eq + a2 * self.ic1eq + a3 * v3;
+
+        self.ic1eq = 2.0 * v1 - self.ic1eq;
+        self.ic2eq = 2.0 * v2 - self.ic2eq;
+
+        let output = match self.mode {
+            Mode::Low => v2,
+            Mode::Band => v1,
+            Mode::High => input - self.k * v1 - v2,
+        };
+
+        patchbay.set(&mut self.output, output);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.ic1eq = 0.0;
+        self.ic2eq = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::Oscillator;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    // Ratio of output to input RMS for a saw wave through a filter at 500Hz
+    fn gain(mode: fn(&mut Filter) -> &mut Filter, frequency: f32) -> f32 {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+        let mut filter = Filter::new(patchbay.point().unwrap());
+
+        osc.set_frequency(frequency).output_saw();
+
+        mode(&mut filter)
+            .set_input(osc.output())
+            .set_cutoff(Signal::Fixed(500.0))
+            .set_resonance(Signal::Fixed(0.0));
+
+        let mut input_energy = 0.0;
+        let mut output_energy = 0.0;
+
+        for i in 0..SAMPLE_RATE / 2 {
+            Module::<SAMPLE_RATE>::process(&mut osc, &mut patchbay);
+            Module::<SAMPLE_RATE>::process(&mut filter, &mut patchbay);
+
+            // Skip the settling time of the filter
+            if i > SAMPLE_RATE / 10 {
+                let input = patchbay.get(osc.output());
+                let output = patchbay.get(filter.output());
+                input_energy += input * input;
+                output_energy += output * output;
+            }
+        }
+
+        math::sqrt(output_energy / input_energy)
+    }
+
+    #[test]
+    fn filter_should_attenuate_high_frequencies_in_low_pass_mode() {
+        assert!(gain(Filter::low_pass, 5000.0) < 0.1);
+        assert!(gain(Filter::low_pass, 50.0) > 0.6);
+    }
+
+    #[test]
+    fn filter_should_pass_high_frequencies_in_high_pass_mode() {
+        assert!(gain(Filter::high_pass, 5000.0) > 0.9);
+        assert!(gain(Filter::high_pass, 50.0) < 0.6);
+    }
+
+    #[test]
+    fn filter_should_pass_the_cutoff_region_in_band_pass_mode() {
+        let band = gain(Filter::band_pass, 500.0);
+
+        assert!(band > gain(Filter::band_pass, 5000.0));
+        assert!(band > gain(Filter::band_pass, 50.0));
+    }
+
+    #[test]
+    fn filter_should_stop_ringing_on_reset() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut filter = Filter::new(patchbay.point().unwrap());
+
+        filter
+            .set_input(input.signal())
+            .set_cutoff(Signal::Fixed(500.0))
+            .set_resonance(Signal::Fixed(0.9));
+
+        patchbay.set(&mut input, 1.0);
+        Module::<SAMPLE_RATE>::process(&mut filter, &mut patchbay);
+        patchbay.set(&mut input, 0.0);
+
+        for _ in 0..10 {
+            Module::<SAMPLE_RATE>::process(&mut filter, &mut patchbay);
+        }
+
+        assert!(patchbay.get(filter.output()) != 0.0);
+
+        Module::<SAMPLE_RATE>::reset(&mut filter, &mut patchbay);
+
+        for _ in 0..10 {
+            Module::<SAMPLE_RATE>::process(&mut filter, &mut patchbay);
+            assert_eq!(patchbay.get(filter.output()), 0.0);
+        }
+    }
+
+    #[test]
+    fn filter_should_wait_for_all_inputs() {
+        let mut patchbay: Patchbay<3> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut filter = Filter::new(patchbay.point().unwrap());
+
+        let mut cutoff = patchbay.point().unwrap();
+
+        filter
+            .set_input(input.signal())
+            .set_cutoff(cutoff.signal())
+            .set_resonance(Signal::Fixed(0.5));
+
+        patchbay.clear_marks();
+        assert!(!Module::<SAMPLE_RATE>::is_ready(&filter, &patchbay));
+
+        patchbay.set(&mut input, 0.0);
+        assert!(!Module::<SAMPLE_RATE>::is_ready(&filter, &patchbay));
+
+        patchbay.set(&mut cutoff, 1000.0);
+        assert!(Module::<SAMPLE_RATE>::is_ready(&filter, &patchbay));
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Boolean operation applied by the [`Logic`] module.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum LogicOp {
+    And,
+    Or,
+    Xor,
+    /// Inverts input `a`, input `b` is ignored.
+    Not,
+}
+
+/// Boolean logic on gate signals.
+///
+/// Inputs are treated as gates (high when >= 0.5), the output is a gate of either 0.0 or 1.0.
+pub struct Logic {
+    a: Signal,
+    b: Signal,
+    op: LogicOp,
+    output: PatchPoint,
+}
+
+impl Logic {
+    pub fn new(output: PatchPoint) -> Self {
+        Logic {
+            a: Signal::None,
+            b: Signal::None,
+            op: LogicOp::And,
+            output,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_a(&mut self, signal: Signal) -> &mut Self {
+        self.a = signal;
+        self
+    }
+
+    pub fn set_b(&mut self, signal: Signal) -> &mut Self {
+        self.b = signal;
+        self
+    }
+
+    pub fn set_op(&mut self, op: LogicOp) -> &mut Self {
+        self.op = op;
+        self
+    }
+
+    pub fn get_op(&self) -> LogicOp {
+        self.op
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Logic {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.a) && patchbay.check(self.b)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let a = patchbay.get(self.a) >= 0.5;
+        let b = patchbay.get(self.b) >= 0.5;
+
+        let result = match self.op {
+            LogicOp::And => a && b,
+            LogicOp::Or => a || b,
+            LogicOp::Xor => a != b,
+            LogicOp::Not => !a,
+        };
+
+        let output = if result { 1.0 } else { 0.0 };
+
+        patchbay.set(&mut self.output, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    fn truth_table(op: LogicOp) -> [f32; 4] {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut logic = Logic::new(patchbay.point().unwrap());
+        let mut results = [0.0; 4];
+
+        logic.set_op(op);
+
+        for (i, &(a, b)) in [(0.0, 0.0), (0.0, 1.0), (1.0, 0.0), (1.0, 1.0)]
+            .iter()
+            .enumerate()
+        {
+            logic.set_a(Signal::Fixed(a)).set_b(Signal::Fixed(b));
+            Module::<SAMPLE_RATE>::process(&mut logic, &mut patchbay);
+            results[i] = patchbay.get(logic.output());
+        }
+
+        results
+    }
+
+    #[test]
+    fn logic_should_and_gates() {
+        assert_eq!(truth_table(LogicOp::And), [0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn logic_should_or_gates() {
+        assert_eq!(truth_table(LogicOp::Or), [0.0, 1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn logic_should_xor_gates() {
+        assert_eq!(truth_table(LogicOp::Xor), [0.0, 1.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn logic_should_invert_a_single_gate() {
+        assert_eq!(truth_table(LogicOp::Not), [1.0, 1.0, 0.0, 0.0]);
+    }
+}
@@ -1,11 +1,34 @@
+use crate::math;
 use crate::{Module, PatchPoint, Patchbay, Signal};
 
 const INPUTS: usize = 16;
 
+/// How a [`Mix`] limits its summed output.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ClipMode {
+    /// Leave the sum untouched
+    Off,
+    /// Clamp the sum between -1.0 and 1.0
+    Hard,
+    /// Bend the sum towards -1.0 and 1.0 using a `tanh` curve
+    Soft,
+}
+
 /// 16 channel summing mixer
+///
+/// Inputs can be muted or soloed, as soon as any input is soloed only soloed inputs are summed.
+/// Muting takes precedence over soloing. Changes to the gain of an input are smoothed to avoid
+/// zipper noise, see [`Mix::set_smoothing`]. The summed output is not limited unless a
+/// [`ClipMode`] is set.
 pub struct Mix {
     output: PatchPoint,
     inputs: [Signal; INPUTS],
+    muted: [bool; INPUTS],
+    soloed: [bool; INPUTS],
+    gains: [f32; INPUTS],
+    current_gains: [f32; INPUTS],
+    smoothing: f32,
+    clip_mode: ClipMode,
 }
 
 impl Mix {
@@ -13,6 +36,12 @@ impl Mix {
         Mix {
             output,
             inputs: [Signal::None; INPUTS],
+            muted: [false; INPUTS],
+            soloed: [false; INPUTS],
+            gains: [1.0; INPUTS],
+            current_gains: [1.0; INPUTS],
+            smoothing: 0.005,
+            clip_mode: ClipMode::Off,
         }
     }
 
@@ -20,8 +49,77 @@ impl Mix {
         self.output.signal()
     }
 
+    /// Add an input at unity gain.
     pub fn add_input(&mut self, input: Signal, index: usize) {
+        self.add_input_with_gain(input, 1.0, index);
+    }
+
+    /// Add an input with a gain, the gain applies immediately without smoothing.
+    pub fn add_input_with_gain(&mut self, input: Signal, gain: f32, index: usize) {
         self.inputs[index] = input;
+        self.gains[index] = gain;
+        self.current_gains[index] = gain;
+    }
+
+    /// Disconnect the input at `index`, resetting its gain, mute and solo state so the channel
+    /// can be reused.
+    pub fn remove_input(&mut self, index: usize) {
+        self.add_input(Signal::None, index);
+        self.muted[index] = false;
+        self.soloed[index] = false;
+    }
+
+    pub fn clear_inputs(&mut self) {
+        for index in 0..INPUTS {
+            self.remove_input(index);
+        }
+    }
+
+    pub fn set_gain(&mut self, index: usize, gain: f32) -> &mut Self {
+        self.gains[index] = gain;
+        self
+    }
+
+    pub fn get_gain(&self, index: usize) -> f32 {
+        self.gains[index]
+    }
+
+    /// Move the applied gains towards newly set gains over roughly `seconds` using a one-pole
+    /// smoother, a value of `0.0` applies gain changes instantly. Defaults to 5ms.
+    pub fn set_smoothing(&mut self, seconds: f32) -> &mut Self {
+        self.smoothing = seconds;
+        self
+    }
+
+    pub fn get_smoothing(&self) -> f32 {
+        self.smoothing
+    }
+
+    pub fn set_clip_mode(&mut self, mode: ClipMode) -> &mut Self {
+        self.clip_mode = mode;
+        self
+    }
+
+    pub fn get_clip_mode(&self) -> ClipMode {
+        self.clip_mode
+    }
+
+    pub fn set_muted(&mut self, index: usize, muted: bool) -> &mut Self {
+        self.muted[index] = muted;
+        self
+    }
+
+    pub fn is_muted(&self, index: usize) -> bool {
+        self.muted[index]
+    }
+
+    pub fn set_solo(&mut self, index: usize, solo: bool) -> &mut Self {
+        self.soloed[index] = solo;
+        self
+    }
+
+    pub fn is_soloed(&self, index: usize) -> bool {
+        self.soloed[index]
     }
 }
 
@@ -31,12 +129,190 @@ impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Mix {
     }
 
     fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let solo = self.soloed.iter().any(|s| *s);
+        let smoothing_samples = self.smoothing * SAMPLE_RATE as f32;
         let mut sum = 0.0;
 
-        for input in self.inputs {
-            sum += patchbay.get(input);
+        for i in 0..INPUTS {
+            // Keep smoothing muted inputs so unmuting picks up the current gain
+            if smoothing_samples > 1.0 {
+                self.current_gains[i] +=
+                    (self.gains[i] - self.current_gains[i]) / smoothing_samples;
+            } else {
+                self.current_gains[i] = self.gains[i];
+            }
+
+            if self.muted[i] || (solo && !self.soloed[i]) {
+                continue;
+            }
+
+            sum += patchbay.get(self.inputs[i]) * self.current_gains[i];
+        }
+
+        let output = match self.clip_mode {
+            ClipMode::Off => sum,
+            ClipMode::Hard => sum.clamp(-1.0, 1.0),
+            ClipMode::Soft => math::tanh(sum),
+        };
+
+        patchbay.set(&mut self.output, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    fn mix() -> (Mix, Patchbay<1>) {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut mix = Mix::new(patchbay.point().unwrap());
+
+        mix.add_input(Signal::Fixed(0.1), 0);
+        mix.add_input(Signal::Fixed(0.2), 1);
+        mix.add_input(Signal::Fixed(0.4), 2);
+
+        (mix, patchbay)
+    }
+
+    #[test]
+    fn mix_should_sum_inputs() {
+        let (mut mix, mut patchbay) = mix();
+
+        Module::<SAMPLE_RATE>::process(&mut mix, &mut patchbay);
+        assert!((patchbay.get(mix.output()) - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn mix_should_leave_muted_inputs_out_of_the_sum() {
+        let (mut mix, mut patchbay) = mix();
+
+        mix.set_muted(1, true);
+        Module::<SAMPLE_RATE>::process(&mut mix, &mut patchbay);
+        assert!((patchbay.get(mix.output()) - 0.5).abs() < 1e-6);
+
+        mix.set_muted(1, false);
+        Module::<SAMPLE_RATE>::process(&mut mix, &mut patchbay);
+        assert!((patchbay.get(mix.output()) - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn mix_should_silence_other_inputs_when_soloing() {
+        let (mut mix, mut patchbay) = mix();
+
+        mix.set_solo(2, true);
+        Module::<SAMPLE_RATE>::process(&mut mix, &mut patchbay);
+        assert!((patchbay.get(mix.output()) - 0.4).abs() < 1e-6);
+
+        mix.set_solo(0, true);
+        Module::<SAMPLE_RATE>::process(&mut mix, &mut patchbay);
+        assert!((patchbay.get(mix.output()) - 0.5).abs() < 1e-6);
+
+        // Muting wins over soloing
+        mix.set_muted(0, true);
+        Module::<SAMPLE_RATE>::process(&mut mix, &mut patchbay);
+        assert!((patchbay.get(mix.output()) - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn mix_should_weigh_inputs_by_their_gain() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut mix = Mix::new(patchbay.point().unwrap());
+
+        mix.add_input_with_gain(Signal::Fixed(0.8), 0.5, 0);
+        mix.add_input_with_gain(Signal::Fixed(0.4), 0.25, 1);
+
+        Module::<SAMPLE_RATE>::process(&mut mix, &mut patchbay);
+        assert!((patchbay.get(mix.output()) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn mix_should_leave_out_removed_inputs() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut mix = Mix::new(patchbay.point().unwrap());
+
+        mix.add_input(Signal::Fixed(0.1), 0);
+        mix.add_input(input.signal(), 1);
+        mix.add_input(Signal::Fixed(0.4), 2);
+
+        patchbay.set(&mut input, 0.2);
+        Module::<SAMPLE_RATE>::process(&mut mix, &mut patchbay);
+        assert!((patchbay.get(mix.output()) - 0.7).abs() < 1e-6);
+
+        mix.remove_input(1);
+
+        // No longer waiting on the removed input
+        patchbay.clear_marks();
+        assert!(Module::<SAMPLE_RATE>::is_ready(&mix, &patchbay));
+
+        Module::<SAMPLE_RATE>::process(&mut mix, &mut patchbay);
+        assert!((patchbay.get(mix.output()) - 0.5).abs() < 1e-6);
+
+        mix.clear_inputs();
+        Module::<SAMPLE_RATE>::process(&mut mix, &mut patchbay);
+        assert_eq!(patchbay.get(mix.output()), 0.0);
+    }
+
+    fn clipped(mode: ClipMode) -> f32 {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut mix = Mix::new(patchbay.point().unwrap());
+
+        mix.add_input(Signal::Fixed(1.5), 0);
+        mix.add_input(Signal::Fixed(0.5), 1);
+        mix.set_clip_mode(mode);
+
+        Module::<SAMPLE_RATE>::process(&mut mix, &mut patchbay);
+        patchbay.get(mix.output())
+    }
+
+    #[test]
+    fn mix_should_not_clip_by_default() {
+        assert_eq!(clipped(ClipMode::Off), 2.0);
+    }
+
+    #[test]
+    fn mix_should_clamp_in_hard_clip_mode() {
+        assert_eq!(clipped(ClipMode::Hard), 1.0);
+    }
+
+    #[test]
+    fn mix_should_bend_in_soft_clip_mode() {
+        let output = clipped(ClipMode::Soft);
+
+        assert!(output > 0.95 && output < 1.0);
+        assert!((output - math::tanh(2.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn mix_should_ramp_gain_changes() {
+        let (mut mix, mut patchbay) = mix();
+
+        mix.set_gain(2, 0.0).set_smoothing(0.0);
+        Module::<SAMPLE_RATE>::process(&mut mix, &mut patchbay);
+        assert!((patchbay.get(mix.output()) - 0.3).abs() < 1e-6);
+
+        // 1ms of smoothing at 48kHz
+        mix.set_gain(2, 1.0).set_smoothing(0.001);
+
+        let mut previous = 0.3;
+
+        for _ in 0..48 {
+            Module::<SAMPLE_RATE>::process(&mut mix, &mut patchbay);
+            let output = patchbay.get(mix.output());
+
+            // Rising in small steps instead of jumping to the new gain
+            assert!(output > previous);
+            assert!(output - previous < 0.4 * 0.05);
+
+            previous = output;
+        }
+
+        for _ in 0..480 {
+            Module::<SAMPLE_RATE>::process(&mut mix, &mut patchbay);
         }
 
-        patchbay.set(&mut self.output, sum);
+        assert!((patchbay.get(mix.output()) - 0.7).abs() < 1e-4);
     }
 }
@@ -1,15 +1,78 @@
 //! Basic flavorless bread and butter modules.
 
+mod auto_gain;
+mod automation_lane;
+mod biquad;
 mod clock;
+mod comparator;
+mod conv_reverb;
+mod crossover;
+mod dc_block;
+mod delay;
+mod drunk;
 mod dummy;
 mod envelope;
+mod envelope_follower;
+mod filter;
+mod logic;
 mod mix;
+mod multiband_width;
+mod noise;
 mod oscillator;
+mod peak;
+mod plucked_string;
+mod poly;
+mod probability;
+mod quantizer;
+mod random_pitch;
+mod ratchet;
+mod resonator;
+mod safety_limiter;
+mod sample_and_hold;
+mod shelf;
+mod shift_register;
+mod slew;
+mod stream_osc;
+mod table_shaper;
+mod trace_recorder;
+mod trance_gate;
 mod vca;
+mod width;
 
+pub use auto_gain::AutoGain;
+pub use automation_lane::AutomationLane;
 pub use clock::Clock;
+pub use comparator::Comparator;
+pub use conv_reverb::ConvReverb;
+pub use crossover::Crossover;
+pub use dc_block::DcBlock;
+pub use delay::Delay;
+pub use drunk::Drunk;
 pub use dummy::Dummy;
 pub use envelope::Envelope;
-pub use mix::Mix;
+pub use envelope_follower::EnvelopeFollower;
+pub use filter::Filter;
+pub use logic::{Logic, LogicOp};
+pub use mix::{ClipMode, Mix};
+pub use multiband_width::MultibandWidth;
+pub use noise::{Noise, NoiseColor};
 pub use oscillator::Oscillator;
+pub use peak::Peak;
+pub use plucked_string::PluckedString;
+pub use poly::Poly;
+pub use probability::Probability;
+pub use quantizer::Quantizer;
+pub use random_pitch::RandomPitch;
+pub use ratchet::Ratchet;
+pub use resonator::Resonator;
+pub use safety_limiter::SafetyLimiter;
+pub use sample_and_hold::SampleAndHold;
+pub use shelf::{Shelf, ShelfMode};
+pub use shift_register::ShiftRegister;
+pub use slew::Slew;
+pub use stream_osc::StreamOsc;
+pub use table_shaper::TableShaper;
+pub use trace_recorder::TraceRecorder;
+pub use trance_gate::TranceGate;
 pub use vca::Vca;
+pub use width::Width;
@@ -0,0 +1,173 @@
+use super::biquad::LinkwitzRiley;
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Stereo width control split into a low, mid and high band.
+///
+/// Only the side (L - R) signal is split into bands using Linkwitz-Riley crossovers, each band
+/// of side signal is scaled by its own width while the mid (L + R) signal is left untouched.
+/// A width of 0.0 collapses the band to mono, 1.0 leaves it as is and larger values widen it.
+/// By default the low band is kept mono and the other bands are left as is.
+pub struct MultibandWidth {
+    left: Signal,
+    right: Signal,
+    output_left: PatchPoint,
+    output_right: PatchPoint,
+    low_frequency: f32,
+    high_frequency: f32,
+    widths: [f32; 3],
+    low_split: LinkwitzRiley,
+    high_split: LinkwitzRiley,
+    update: bool,
+}
+
+impl MultibandWidth {
+    pub fn new(output_left: PatchPoint, output_right: PatchPoint) -> Self {
+        MultibandWidth {
+            left: Signal::None,
+            right: Signal::None,
+            output_left,
+            output_right,
+            low_frequency: 200.0,
+            high_frequency: 4000.0,
+            widths: [0.0, 1.0, 1.0],
+            low_split: LinkwitzRiley::new(),
+            high_split: LinkwitzRiley::new(),
+            update: true,
+        }
+    }
+
+    pub fn output_left(&self) -> Signal {
+        self.output_left.signal()
+    }
+
+    pub fn output_right(&self) -> Signal {
+        self.output_right.signal()
+    }
+
+    pub fn set_left(&mut self, signal: Signal) -> &mut Self {
+        self.left = signal;
+        self
+    }
+
+    pub fn set_right(&mut self, signal: Signal) -> &mut Self {
+        self.right = signal;
+        self
+    }
+
+    /// Set the crossover frequencies between the low and mid, and mid and high band in Hz.
+    pub fn set_crossovers(&mut self, low: f32, high: f32) -> &mut Self {
+        self.low_frequency = low;
+        self.high_frequency = high;
+        self.update = true;
+        self
+    }
+
+    pub fn get_crossovers(&self) -> (f32, f32) {
+        (self.low_frequency, self.high_frequency)
+    }
+
+    /// Set the width of the low, mid and high band.
+    pub fn set_widths(&mut self, low: f32, mid: f32, high: f32) -> &mut Self {
+        self.widths = [low, mid, high];
+        self
+    }
+
+    pub fn get_widths(&self) -> (f32, f32, f32) {
+        (self.widths[0], self.widths[1], self.widths[2])
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for MultibandWidth {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.left) && patchbay.check(self.right)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        if self.update {
+            self.low_split
+                .set_frequency(SAMPLE_RATE, self.low_frequency);
+            self.high_split
+                .set_frequency(SAMPLE_RATE, self.high_frequency);
+            self.update = false;
+        }
+
+        let left = patchbay.get(self.left);
+        let right = patchbay.get(self.right);
+
+        let mid = (left + right) * 0.5;
+        let side = (left - right) * 0.5;
+
+        let (low, rest) = self.low_split.process(side);
+        let (band, high) = self.high_split.process(rest);
+
+        let side = low * self.widths[0] + band * self.widths[1] + high * self.widths[2];
+
+        patchbay.set(&mut self.output_left, mid + side);
+        patchbay.set(&mut self.output_right, mid - side);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.low_split.clear();
+        self.high_split.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math;
+    use core::f32::consts::TAU;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    // Feed a sine panned towards the left and measure the output side to input side ratio
+    fn side_gain(frequency: f32) -> f32 {
+        let mut patchbay: Patchbay<4> = Patchbay::new();
+        let mut left = patchbay.point().unwrap();
+        let mut right = patchbay.point().unwrap();
+        let mut width = MultibandWidth::new(patchbay.point().unwrap(), patchbay.point().unwrap());
+
+        width
+            .set_left(left.signal())
+            .set_right(right.signal())
+            .set_crossovers(200.0, 4000.0)
+            .set_widths(0.0, 1.0, 2.0);
+
+        let mut input_energy = 0.0;
+        let mut output_energy = 0.0;
+
+        for i in 0..SAMPLE_RATE / 2 {
+            let sample = math::sin(TAU * frequency * i as f32 / SAMPLE_RATE as f32);
+
+            patchbay.set(&mut left, sample);
+            patchbay.set(&mut right, sample * 0.5);
+            Module::<SAMPLE_RATE>::process(&mut width, &mut patchbay);
+
+            // Skip the settling time of the filters
+            if i > SAMPLE_RATE / 10 {
+                let side =
+                    (patchbay.get(width.output_left()) - patchbay.get(width.output_right())) * 0.5;
+
+                input_energy += (sample * 0.25) * (sample * 0.25);
+                output_energy += side * side;
+            }
+        }
+
+        math::sqrt(output_energy / input_energy)
+    }
+
+    #[test]
+    fn multiband_width_should_keep_the_low_band_mono() {
+        assert!(side_gain(50.0) < 0.01);
+    }
+
+    #[test]
+    fn multiband_width_should_widen_the_high_band() {
+        assert!((side_gain(12_000.0) - 2.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn multiband_width_should_leave_the_mid_band() {
+        assert!((side_gain(1000.0) - 1.0).abs() < 0.1);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::random::Random;
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+// Leak of the brown noise integrator, keeps it from drifting off with a corner around 15Hz
+const LEAK: f32 = 0.998;
+
+// Gains bringing the RMS level of pink and brown noise in line with white noise
+const PINK_GAIN: f32 = 0.336;
+const BROWN_GAIN: f32 = 0.0632;
+
+/// Spectrum of the noise generated by a [`Noise`] module.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum NoiseColor {
+    /// Equal energy at all frequencies
+    White,
+    /// Energy falling off 3dB per octave
+    Pink,
+    /// Energy falling off 6dB per octave
+    Brown,
+}
+
+/// Noise generator
+///
+/// All colors are level compensated to roughly the same RMS level as white noise, so switching
+/// colors using [`Noise::set_color`] doesn't cause jumps in loudness. Use [`Noise::set_seed`]
+/// for reproducible noise.
+pub struct Noise {
+    output: PatchPoint,
+    color: NoiseColor,
+    random: Random,
+    pink: [f32; 3],
+    brown: f32,
+}
+
+impl Noise {
+    pub fn new(output: PatchPoint) -> Self {
+        Noise {
+            output,
+            color: NoiseColor::White,
+            random: Random::new(1),
+            pink: [0.0; 3],
+            brown: 0.0,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_color(&mut self, color: NoiseColor) -> &mut Self {
+        self.color = color;
+        self
+    }
+
+    pub fn get_color(&self) -> NoiseColor {
+        self.color
+    }
+
+    pub fn set_seed(&mut self, seed: u32) -> &mut Self {
+        self.random = Random::new(seed);
+        self
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Noise {
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let white = self.random.next_bipolar();
+
+        // Keep all filters running so switching colors doesn't start from silence, pink noise
+        // uses Paul Kellet's economy filter
+        self.pink[0] = 0.99765 * self.pink[0] + white * 0.099046;
+        self.pink[1] = 0.963 * self.pink[1] + white * 0.2965164;
+        self.pink[2] = 0.57 * self.pink[2] + white * 1.0526913;
+
+        self.brown = LEAK * self.brown + white * BROWN_GAIN;
+
+        let output = match self.color {
+            NoiseColor::White => white,
+            NoiseColor::Pink => {
+                (self.pink[0] + self.pink[1] + self.pink[2] + white * 0.1848) * PINK_GAIN
+            }
+            NoiseColor::Brown => self.brown,
+        };
+
+        patchbay.set(&mut self.output, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    // RMS level of the full output and of the output low passed around 80Hz
+    fn levels(color: NoiseColor) -> (f32, f32) {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut noise = Noise::new(patchbay.point().unwrap());
+
+        noise.set_color(color);
+
+        let (mut sum, mut low_sum, mut low) = (0.0, 0.0, 0.0);
+
+        for _ in 0..SAMPLE_RATE * 2 {
+            Module::<SAMPLE_RATE>::process(&mut noise, &mut patchbay);
+            let value = patchbay.get(noise.output());
+
+            low += (value - low) * 0.01;
+            sum += value * value;
+            low_sum += low * low;
+        }
+
+        let samples = (SAMPLE_RATE * 2) as f32;
+
+        (math::sqrt(sum / samples), math::sqrt(low_sum / samples))
+    }
+
+    #[test]
+    fn noise_colors_should_have_comparable_levels() {
+        let (white, _) = levels(NoiseColor::White);
+        let (pink, _) = levels(NoiseColor::Pink);
+        let (brown, _) = levels(NoiseColor::Brown);
+
+        assert!((white - 0.577).abs() < 0.01);
+        assert!((pink / white - 1.0).abs() < 0.2);
+        assert!((brown / white - 1.0).abs() < 0.2);
+    }
+
+    #[test]
+    fn brown_noise_should_have_more_low_frequency_energy() {
+        let (_, white) = levels(NoiseColor::White);
+        let (_, pink) = levels(NoiseColor::Pink);
+        let (_, brown) = levels(NoiseColor::Brown);
+
+        assert!(pink > white * 2.0);
+        assert!(brown > pink);
+    }
+}
@@ -1,6 +1,6 @@
-use crate::{Module, PatchPoint, Patchbay, Signal};
-
-const PI: f32 = 3.141;
+use crate::math::floor;
+use crate::{Module, ParamId, PatchPoint, Patchbay, Ramp, Signal};
+use core::f32::consts::PI;
 
 enum Waveform {
     Sine,
@@ -13,8 +13,30 @@ enum Waveform {
 pub struct Oscillator {
     wave_shape: Waveform,
     frequency: f32,
+    current_frequency: f32,
+    smoothing: f32,
+    started: bool,
     amplitude: f32,
+    offset: f32,
     output: PatchPoint,
+    sub_output: Option<PatchPoint>,
+    sub_octave: i8,
+    sub_cycle: u8,
+    antialiased: bool,
+    integrator: f32,
+    sync: Signal,
+    previous_sync: f32,
+    fm: Signal,
+    fm_depth: f32,
+    pm: Signal,
+    pm_depth: f32,
+    clock: Signal,
+    clock_division: f32,
+    previous_clock: f32,
+    clock_samples: Option<usize>,
+    clock_period: usize,
+    frequency_ramp: Ramp,
+    amplitude_ramp: Ramp,
     value: f32,
 }
 
@@ -23,18 +45,117 @@ impl Oscillator {
         Oscillator {
             wave_shape: Waveform::Sine,
             frequency: 440.0,
+            current_frequency: 440.0,
+            smoothing: 0.0,
+            started: false,
             amplitude: 0.8,
+            offset: 0.0,
             output,
+            sub_output: None,
+            sub_octave: -1,
+            sub_cycle: 0,
+            antialiased: false,
+            integrator: 0.0,
+            sync: Signal::None,
+            previous_sync: 0.0,
+            fm: Signal::None,
+            fm_depth: 0.0,
+            pm: Signal::None,
+            pm_depth: 0.0,
+            clock: Signal::None,
+            clock_division: 1.0,
+            previous_clock: 0.0,
+            clock_samples: None,
+            clock_period: 0,
+            frequency_ramp: Ramp::new(),
+            amplitude_ramp: Ramp::new(),
             value: 0.0,
         }
     }
 
+    /// Frequency parameter for [`Module::ramp`]
+    pub const FREQUENCY: ParamId = 0;
+    /// Amplitude parameter for [`Module::ramp`]
+    pub const AMPLITUDE: ParamId = 1;
+
     pub fn output(&self) -> Signal {
         self.output.signal()
     }
 
+    /// Square wave output one or two octaves below the main pitch, [`Signal::None`] until a
+    /// point has been set using [`Oscillator::set_sub_output`].
+    pub fn sub(&self) -> Signal {
+        self.sub_output
+            .as_ref()
+            .map_or(Signal::None, |point| point.signal())
+    }
+
+    /// Enable the sub oscillator by giving it a point to output to.
+    pub fn set_sub_output(&mut self, point: PatchPoint) -> &mut Self {
+        self.sub_output = Some(point);
+        self
+    }
+
+    /// Set the amount of octaves the sub oscillator sits below the main pitch, either -1 or -2.
+    pub fn set_sub_octave(&mut self, octave: i8) -> &mut Self {
+        self.sub_octave = octave.clamp(-2, -1);
+        self
+    }
+
+    pub fn get_sub_octave(&self) -> i8 {
+        self.sub_octave
+    }
+
+    /// Hard sync the oscillator, every rising edge of the signal restarts the cycle.
+    pub fn set_sync(&mut self, signal: Signal) -> &mut Self {
+        self.sync = signal;
+        self
+    }
+
+    /// Linear frequency modulation, the signal multiplied by `depth` in Hz is added to the
+    /// frequency.
+    pub fn set_fm(&mut self, signal: Signal, depth: f32) -> &mut Self {
+        self.fm = signal;
+        self.fm_depth = depth;
+        self
+    }
+
+    /// Phase modulation, the signal multiplied by `depth` in cycles offsets the phase before
+    /// the waveform is generated.
+    pub fn set_pm(&mut self, signal: Signal, depth: f32) -> &mut Self {
+        self.pm = signal;
+        self.pm_depth = depth;
+        self
+    }
+
+    /// Follow the tempo of a clock, once the time between two rising edges has been measured
+    /// the frequency is derived from it instead of the set frequency.
+    pub fn set_clock(&mut self, signal: Signal) -> &mut Self {
+        self.clock = signal;
+        self.clock_samples = None;
+        self.clock_period = 0;
+        self
+    }
+
+    /// Set the amount of cycles per clock pulse as a fraction, e.g. `1, 4` completes a cycle
+    /// every four pulses. Defaults to a cycle per pulse.
+    pub fn set_clock_division(&mut self, numerator: u32, denominator: u32) -> &mut Self {
+        self.clock_division = numerator as f32 / denominator.max(1) as f32;
+        self
+    }
+
+    pub fn get_clock_division(&self) -> f32 {
+        self.clock_division
+    }
+
     pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
         self.frequency = frequency;
+
+        // Start out at the set frequency instead of gliding from the default
+        if !self.started {
+            self.current_frequency = frequency;
+        }
+
         self
     }
 
@@ -42,6 +163,50 @@ impl Oscillator {
         self.frequency
     }
 
+    /// Nudge the frequency to the nearest one fitting a whole number of cycles (at least one)
+    /// in `loop_length` samples, so the phase returns to the start for seamless loops.
+    pub fn phase_align<const SAMPLE_RATE: usize>(&mut self, loop_length: usize) -> &mut Self {
+        let cycles = self.frequency * loop_length as f32 / SAMPLE_RATE as f32;
+        let whole = floor(cycles + 0.5).max(1.0);
+
+        self.frequency = whole * SAMPLE_RATE as f32 / loop_length.max(1) as f32;
+        self.current_frequency = self.frequency;
+        self
+    }
+
+    /// Fraction of a cycle the phase is off from the start after `loop_length` samples at the
+    /// current frequency, between -0.5 and 0.5.
+    pub fn phase_error<const SAMPLE_RATE: usize>(&self, loop_length: usize) -> f32 {
+        let cycles = self.frequency * loop_length as f32 / SAMPLE_RATE as f32;
+
+        cycles - floor(cycles + 0.5)
+    }
+
+    /// Glide towards a newly set frequency over roughly `seconds` using a one-pole smoother,
+    /// a value of `0.0` (default) applies frequency changes instantly. The frequency set before
+    /// the first sample is processed is always applied instantly.
+    pub fn set_smoothing(&mut self, seconds: f32) -> &mut Self {
+        self.smoothing = seconds;
+        self
+    }
+
+    pub fn get_smoothing(&self) -> f32 {
+        self.smoothing
+    }
+
+    /// Reduce aliasing at higher frequencies, the saw and pulse get PolyBLEP corrected edges and
+    /// the triangle is generated by integrating a band-limited square.
+    pub fn set_antialiasing(&mut self, antialiased: bool) -> &mut Self {
+        self.antialiased = antialiased;
+        // Start the integrator in phase with the naive triangle
+        self.integrator = triangle(self.value);
+        self
+    }
+
+    pub fn get_antialiasing(&self) -> bool {
+        self.antialiased
+    }
+
     pub fn set_amplitude(&mut self, amplitude: f32) -> &mut Self {
         self.amplitude = amplitude;
         self
@@ -51,6 +216,17 @@ impl Oscillator {
         self.amplitude
     }
 
+    /// Add a constant to the output after the amplitude has been applied, e.g. an amplitude of
+    /// 0.5 with an offset of 0.5 turns the output into a unipolar 0.0 to 1.0 modulation source.
+    pub fn set_offset(&mut self, offset: f32) -> &mut Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn get_offset(&self) -> f32 {
+        self.offset
+    }
+
     pub fn output_sine(&mut self) -> &mut Self {
         self.wave_shape = Waveform::Sine;
         self
@@ -73,33 +249,157 @@ impl Oscillator {
 }
 
 impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Oscillator {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.sync)
+            && patchbay.check(self.fm)
+            && patchbay.check(self.pm)
+            && patchbay.check(self.clock)
+    }
+
     fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        // Measure the period of the clock
+        let clock = patchbay.get(self.clock);
+        let samples = self.clock_samples.map(|samples| samples.saturating_add(1));
+
+        if clock >= 0.5 && self.previous_clock < 0.5 {
+            if let Some(period) = samples {
+                self.clock_period = period;
+            }
+
+            self.clock_samples = Some(0);
+        } else {
+            self.clock_samples = samples;
+        }
+
+        self.previous_clock = clock;
+
+        let frequency = if self.clock_period > 0 {
+            SAMPLE_RATE as f32 * self.clock_division / self.clock_period as f32
+        } else {
+            self.frequency
+        };
+
+        self.started = true;
+
+        // Move the frequency towards the target
+        let smoothing_samples = self.smoothing * SAMPLE_RATE as f32;
+
+        if smoothing_samples > 1.0 {
+            self.current_frequency += (frequency - self.current_frequency) / smoothing_samples;
+        } else {
+            self.current_frequency = frequency;
+        }
+
         // Ramp up from -1.0 to 1.0 based on the set `frequency`
-        // then use this value to convert to the specific waveforms
-        self.value += (1.0 / SAMPLE_RATE as f32) * self.frequency;
+        // then use this value to convert to the specific waveforms.
+        //
+        // Negative frequencies (e.g. through-zero FM) ramp down instead, the phase keeps moving
+        // continuously through the zero point and wraps around in both directions.
+        //
+        // A full cycle spans 2.0, so the phase moves twice the frequency every second.
+        let frequency = self.current_frequency + patchbay.get(self.fm) * self.fm_depth;
+        let increment = (2.0 / SAMPLE_RATE as f32) * frequency;
+
+        // Every main cycle advances the sub oscillator, which completes a cycle every
+        // 2 or 4 main cycles depending on the octave
+        let sub_cycles = 1 << self.sub_octave.unsigned_abs();
+
+        let sync = patchbay.get(self.sync);
+        let synced = sync >= 0.5 && self.previous_sync < 0.5;
+        self.previous_sync = sync;
+
+        if synced {
+            // Restart the cycle from the starting phase
+            self.value = 0.0;
+            self.sub_cycle = 0;
+            self.integrator = triangle(self.value);
+        } else {
+            self.value += increment;
+        }
 
         // Wrap around
         if self.value >= 1.0 {
             self.value -= 2.0;
+            self.sub_cycle = (self.sub_cycle + 1) % sub_cycles;
+        } else if self.value < -1.0 {
+            self.value += 2.0;
+            self.sub_cycle = (self.sub_cycle + sub_cycles - 1) % sub_cycles;
         }
 
+        // Offset the phase, a full cycle spans 2.0
+        let mut phase = self.value + patchbay.get(self.pm) * self.pm_depth * 2.0;
+        phase -= floor((phase + 1.0) * 0.5) * 2.0;
+
         // Create the desired waveform
         let wave = match self.wave_shape {
-            Waveform::Saw => self.value,
-            Waveform::Sine => sine(self.value),
-            Waveform::Triangle => triangle(self.value),
-            Waveform::Pulse(duty_cycle) => pulse(self.value, duty_cycle),
+            Waveform::Saw if self.antialiased => blep_saw(phase, increment.abs() * 0.5),
+            Waveform::Saw => phase,
+            Waveform::Sine => sine(phase),
+            Waveform::Triangle if self.antialiased => {
+                // Integrate a band-limited square, the slope of the triangle is twice the
+                // phase increment. A small leak keeps the integrator from drifting away, scaled
+                // by the increment so it takes the same share of every cycle at any frequency.
+                let square = blep_square(phase, increment.abs() * 0.5);
+                self.integrator += square * increment * 2.0;
+                self.integrator -= self.integrator * TRIANGLE_LEAK * increment.abs();
+                self.integrator
+            }
+            Waveform::Triangle => triangle(phase),
+            Waveform::Pulse(duty_cycle) if self.antialiased => {
+                blep_pulse(phase, duty_cycle, increment.abs() * 0.5)
+            }
+            Waveform::Pulse(duty_cycle) => pulse(phase, duty_cycle),
         };
 
-        // Set the amplitude
-        let output = wave * self.amplitude;
+        // Set the amplitude and offset
+        let output = wave * self.amplitude + self.offset;
 
         // Update the output value in the patchbay.
         patchbay.set(&mut self.output, output);
+
+        if let Some(point) = self.sub_output.as_mut() {
+            let sub = if self.sub_cycle < sub_cycles / 2 {
+                1.0
+            } else {
+                -1.0
+            };
+
+            patchbay.set(point, sub * self.amplitude);
+        }
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.value = 0.0;
+        self.sub_cycle = 0;
+        self.integrator = triangle(0.0);
+        self.current_frequency = self.frequency;
+        self.started = false;
+        self.previous_sync = 0.0;
+        self.previous_clock = 0.0;
+        self.clock_samples = None;
+    }
+
+    fn ramp(&mut self, param: ParamId, target: f32, samples: usize) {
+        match param {
+            Self::FREQUENCY => self.frequency_ramp.start(self.frequency, target, samples),
+            Self::AMPLITUDE => self.amplitude_ramp.start(self.amplitude, target, samples),
+            _ => (),
+        }
+    }
+
+    fn advance_ramps(&mut self) {
+        self.frequency_ramp.advance(&mut self.frequency);
+        self.amplitude_ramp.advance(&mut self.amplitude);
     }
 }
 
+#[cfg(feature = "libm")]
+fn sine(input: f32) -> f32 {
+    crate::math::sin(input * PI)
+}
+
 // Bashkara approximation of a sine
+#[cfg(not(feature = "libm"))]
 fn sine(input: f32) -> f32 {
     // Calculate with positive values only
     let x = if input < 0.0 { -input * PI } else { input * PI };
@@ -119,7 +419,56 @@ fn triangle(input: f32) -> f32 {
     if input < 0.0 {
         (input + 1.0) * 2.0 - 1.0
     } else {
-        (input * 2.0) * -1.0 + 1.0
+        1.0 - input * 2.0
+    }
+}
+
+// Leak of the integrator used for the anti-aliased triangle, per unit of phase increment
+const TRIANGLE_LEAK: f32 = 0.01;
+
+// Square that is high on the rising half of the triangle, with PolyBLEP corrected edges
+fn blep_square(input: f32, dt: f32) -> f32 {
+    // Phase between 0.0 and 1.0
+    let t = (input + 1.0) * 0.5;
+    let square = if t < 0.5 { 1.0 } else { -1.0 };
+
+    let mut half = t + 0.5;
+    if half >= 1.0 {
+        half -= 1.0;
+    }
+
+    square + poly_blep(t, dt) - poly_blep(half, dt)
+}
+
+// Saw with a PolyBLEP corrected edge, the same correction holds for a falling ramp
+fn blep_saw(input: f32, dt: f32) -> f32 {
+    input - poly_blep((input + 1.0) * 0.5, dt)
+}
+
+// Pulse with PolyBLEP corrected edges, falling at the wrap and rising at the duty cycle
+fn blep_pulse(input: f32, duty_cycle: f32, dt: f32) -> f32 {
+    let t = (input + 1.0) * 0.5;
+
+    let mut rising = t - duty_cycle;
+    if rising < 0.0 {
+        rising += 1.0;
+    }
+
+    pulse(input, duty_cycle) + poly_blep(rising, dt) - poly_blep(t, dt)
+}
+
+// Polynomial residual of a band-limited step around a discontinuity at phase 0.0
+fn poly_blep(t: f32, dt: f32) -> f32 {
+    if dt <= 0.0 {
+        0.0
+    } else if t < dt {
+        let t = t / dt;
+        t + t - t * t - 1.0
+    } else if t > 1.0 - dt {
+        let t = (t - 1.0) / dt;
+        t * t + t + t + 1.0
+    } else {
+        0.0
     }
 }
 
@@ -133,3 +482,601 @@ fn pulse(input: f32, duty_cycle: f32) -> f32 {
         -1.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    fn increments<const N: usize>(osc: &mut Oscillator, patchbay: &mut Patchbay<1>) -> [f32; N] {
+        let mut increments = [0.0; N];
+
+        for increment in increments.iter_mut() {
+            let previous = osc.value;
+            Module::<SAMPLE_RATE>::process(osc, patchbay);
+
+            let mut difference = osc.value - previous;
+
+            // Undo the wrap around
+            if difference < 0.0 {
+                difference += 2.0;
+            }
+
+            *increment = difference;
+        }
+
+        increments
+    }
+
+    #[test]
+    fn oscillator_should_change_frequency_instantly_by_default() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+
+        osc.set_frequency(100.0);
+        increments::<4>(&mut osc, &mut patchbay);
+
+        osc.set_frequency(1000.0);
+        let steps = increments::<4>(&mut osc, &mut patchbay);
+
+        for step in steps {
+            assert!((step - 2000.0 / SAMPLE_RATE as f32).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn oscillator_should_complete_cycles_at_the_set_frequency() {
+        for frequency in [1.0, 100.0, 440.0, 1000.0] {
+            let mut patchbay: Patchbay<1> = Patchbay::new();
+            let mut osc = Oscillator::new(patchbay.point().unwrap());
+            let mut cycles = 0;
+
+            osc.set_frequency(frequency);
+
+            // A second worth of samples
+            for _ in 0..SAMPLE_RATE {
+                let previous = osc.value;
+                Module::<SAMPLE_RATE>::process(&mut osc, &mut patchbay);
+
+                if osc.value < previous {
+                    cycles += 1;
+                }
+            }
+
+            assert_eq!(cycles, frequency as usize);
+        }
+    }
+
+    #[test]
+    fn oscillator_should_glide_to_a_new_frequency_with_smoothing() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+
+        osc.set_frequency(100.0).set_smoothing(0.001);
+        increments::<480>(&mut osc, &mut patchbay);
+
+        osc.set_frequency(1000.0);
+        let steps = increments::<480>(&mut osc, &mut patchbay);
+
+        let start = 200.0 / SAMPLE_RATE as f32;
+        let target = 2000.0 / SAMPLE_RATE as f32;
+
+        // No sudden jump to the target
+        assert!(steps[0] < start + (target - start) * 0.1);
+
+        // Continuously rising towards the target
+        for pair in steps.windows(2) {
+            assert!(pair[1] >= pair[0]);
+            assert!(pair[1] - pair[0] < (target - start) * 0.1);
+        }
+
+        // Ending up at the target after several times the smoothing time
+        assert!((steps[479] - target).abs() < target * 0.01);
+    }
+
+    #[test]
+    fn oscillator_should_start_at_the_set_frequency_with_smoothing() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+
+        osc.set_smoothing(0.01).set_frequency(100.0);
+        let steps = increments::<1>(&mut osc, &mut patchbay);
+
+        // Rather than gliding down from the default of 440Hz
+        assert!((steps[0] - 200.0 / SAMPLE_RATE as f32).abs() < 1e-6);
+    }
+
+    #[test]
+    fn oscillator_should_restart_its_phase_on_reset() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+        let mut first = [0.0; 64];
+        let mut again = [0.0; 64];
+
+        osc.output_saw().set_frequency(330.0);
+
+        for sample in first.iter_mut() {
+            Module::<SAMPLE_RATE>::process(&mut osc, &mut patchbay);
+            *sample = patchbay.get(osc.output());
+        }
+
+        for _ in 0..37 {
+            Module::<SAMPLE_RATE>::process(&mut osc, &mut patchbay);
+        }
+
+        Module::<SAMPLE_RATE>::reset(&mut osc, &mut patchbay);
+
+        for sample in again.iter_mut() {
+            Module::<SAMPLE_RATE>::process(&mut osc, &mut patchbay);
+            *sample = patchbay.get(osc.output());
+        }
+
+        assert_eq!(first, again);
+    }
+
+    #[test]
+    fn oscillator_should_align_its_phase_to_a_loop_length() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+
+        osc.output_saw().set_frequency(440.0);
+
+        // 9.17 cycles in 1000 samples
+        assert!((osc.phase_error::<SAMPLE_RATE>(1000) - 0.1667).abs() < 1e-3);
+
+        osc.phase_align::<SAMPLE_RATE>(1000);
+
+        assert_eq!(osc.get_frequency(), 432.0);
+        assert!(osc.phase_error::<SAMPLE_RATE>(1000).abs() < 1e-4);
+
+        let mut first = [0.0; 1000];
+
+        for sample in first.iter_mut() {
+            Module::<SAMPLE_RATE>::process(&mut osc, &mut patchbay);
+            *sample = patchbay.get(osc.output());
+        }
+
+        // The second pass through the loop starts where the first one started
+        for sample in first.iter().take(100) {
+            Module::<SAMPLE_RATE>::process(&mut osc, &mut patchbay);
+            assert!((patchbay.get(osc.output()) - sample).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn sine_should_follow_a_reference_sine() {
+        // The approximation trades accuracy for speed
+        let tolerance = if cfg!(feature = "libm") { 1e-6 } else { 2e-3 };
+
+        for i in -100..=100 {
+            let x = i as f32 / 100.0;
+            assert!((sine(x) - (x * PI).sin()).abs() < tolerance);
+        }
+    }
+
+    #[test]
+    fn oscillator_should_keep_phase_continuous_through_zero_frequency() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+        let max_increment = 2.0 * 200.0 / SAMPLE_RATE as f32;
+
+        for i in 0..SAMPLE_RATE {
+            // Sweep from 200Hz down to -200Hz
+            let frequency = 200.0 - 400.0 * (i as f32 / SAMPLE_RATE as f32);
+            osc.set_frequency(frequency);
+
+            let previous = osc.value;
+            Module::<SAMPLE_RATE>::process(&mut osc, &mut patchbay);
+
+            assert!(osc.value >= -1.0 && osc.value < 1.0);
+
+            // Account for wrapping around in either direction
+            let mut difference = osc.value - previous;
+
+            if difference > 1.0 {
+                difference -= 2.0;
+            } else if difference < -1.0 {
+                difference += 2.0;
+            }
+
+            assert!(difference.abs() <= max_increment + 1e-6);
+            assert_eq!(difference >= 0.0, frequency >= 0.0);
+        }
+    }
+
+    fn sub_period(octave: i8) -> (usize, usize) {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+
+        osc.set_frequency(1000.0)
+            .set_sub_output(patchbay.point().unwrap())
+            .set_sub_octave(octave)
+            .output_saw();
+
+        let mut main_wraps = [0; 3];
+        let mut sub_edges = [0; 3];
+        let (mut main_count, mut sub_count) = (0, 0);
+        let mut previous = (0.0, 0.0);
+
+        for i in 0..SAMPLE_RATE / 10 {
+            Module::<SAMPLE_RATE>::process(&mut osc, &mut patchbay);
+
+            let main = patchbay.get(osc.output());
+            let sub = patchbay.get(osc.sub());
+
+            if main < previous.0 && main_count < 3 {
+                main_wraps[main_count] = i;
+                main_count += 1;
+            }
+
+            if sub > previous.1 && sub_count < 3 {
+                sub_edges[sub_count] = i;
+                sub_count += 1;
+            }
+
+            previous = (main, sub);
+        }
+
+        (main_wraps[2] - main_wraps[1], sub_edges[2] - sub_edges[1])
+    }
+
+    #[test]
+    fn oscillator_should_output_a_sub_octave() {
+        let (main, sub) = sub_period(-1);
+        assert_eq!(main, 48);
+        assert_eq!(sub, main * 2);
+
+        let (main, sub) = sub_period(-2);
+        assert_eq!(main, 48);
+        assert_eq!(sub, main * 4);
+    }
+
+    #[test]
+    fn triangle_should_be_continuous_and_symmetric_across_the_wrap() {
+        for amplitude in [0.25, 0.5, 1.0] {
+            let mut patchbay: Patchbay<1> = Patchbay::new();
+            let mut osc = Oscillator::new(patchbay.point().unwrap());
+
+            osc.set_frequency(1000.0)
+                .set_amplitude(amplitude)
+                .output_triangle();
+
+            // The steepest the triangle can move in a single sample
+            let max_step = 4.0 * 1000.0 / SAMPLE_RATE as f32 * amplitude;
+
+            let mut previous = None;
+            let (mut min, mut max) = (0.0f32, 0.0f32);
+
+            for _ in 0..SAMPLE_RATE / 10 {
+                Module::<SAMPLE_RATE>::process(&mut osc, &mut patchbay);
+                let value = patchbay.get(osc.output());
+
+                if let Some(previous) = previous {
+                    let step: f32 = value - previous;
+                    assert!(step.abs() <= max_step + 1e-5);
+                }
+
+                previous = Some(value);
+                min = min.min(value);
+                max = max.max(value);
+            }
+
+            assert!((max - amplitude).abs() < max_step);
+            assert!((min + amplitude).abs() < max_step);
+        }
+    }
+
+    #[test]
+    fn pulse_should_be_symmetric_at_half_duty_cycle() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+
+        osc.set_frequency(1000.0)
+            .set_amplitude(0.5)
+            .output_pulse(0.5);
+
+        let (mut high, mut low): (i32, i32) = (0, 0);
+
+        // Exactly 100 periods
+        for _ in 0..SAMPLE_RATE / 10 {
+            Module::<SAMPLE_RATE>::process(&mut osc, &mut patchbay);
+
+            let value = patchbay.get(osc.output());
+
+            if value == 0.5 {
+                high += 1;
+            } else if value == -0.5 {
+                low += 1;
+            } else {
+                panic!("unexpected pulse value {}", value);
+            }
+        }
+
+        assert!((high - low).abs() <= 100);
+    }
+
+    // Ratio of the energy outside of the harmonics to the energy of the harmonics
+    fn aliasing(antialiased: bool, shape: fn(&mut Oscillator) -> &mut Oscillator) -> f64 {
+        const LENGTH: usize = 4800;
+
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+        let mut samples = [0.0; LENGTH];
+
+        // A whole number of cycles fit in the window, so every harmonic lands exactly on a bin
+        let frequency = 4700.0;
+        shape(osc.set_frequency(frequency).set_antialiasing(antialiased));
+
+        // Let the integrator settle
+        for _ in 0..SAMPLE_RATE {
+            Module::<SAMPLE_RATE>::process(&mut osc, &mut patchbay);
+        }
+
+        for sample in samples.iter_mut() {
+            Module::<SAMPLE_RATE>::process(&mut osc, &mut patchbay);
+            *sample = patchbay.get(osc.output()) as f64;
+        }
+
+        let fundamental = (frequency as f64 * LENGTH as f64 / SAMPLE_RATE as f64).round() as usize;
+        let (mut harmonic, mut alias) = (0.0, 0.0);
+
+        for bin in 1..LENGTH / 2 {
+            let (mut re, mut im) = (0.0, 0.0);
+
+            for (n, sample) in samples.iter().enumerate() {
+                let angle = core::f64::consts::TAU * (bin * n % LENGTH) as f64 / LENGTH as f64;
+                re += sample * angle.cos();
+                im -= sample * angle.sin();
+            }
+
+            let energy = re * re + im * im;
+
+            if bin % fundamental == 0 {
+                harmonic += energy;
+            } else {
+                alias += energy;
+            }
+        }
+
+        alias / harmonic
+    }
+
+    #[test]
+    fn antialiased_triangle_should_reduce_aliasing() {
+        let naive = aliasing(false, Oscillator::output_triangle);
+        let antialiased = aliasing(true, Oscillator::output_triangle);
+
+        assert!(antialiased < naive * 0.25);
+    }
+
+    #[test]
+    fn antialiased_triangle_should_keep_its_shape_at_any_frequency() {
+        for frequency in [1.0, 2.0, 5.0, 20.0, 1000.0] {
+            let mut patchbay: Patchbay<2> = Patchbay::new();
+            let mut naive = Oscillator::new(patchbay.point().unwrap());
+            let mut antialiased = Oscillator::new(patchbay.point().unwrap());
+
+            naive
+                .set_frequency(frequency)
+                .set_amplitude(0.8)
+                .output_triangle();
+            antialiased
+                .set_frequency(frequency)
+                .set_amplitude(0.8)
+                .set_antialiasing(true)
+                .output_triangle();
+
+            let (mut max, mut difference) = (0.0f32, 0.0f32);
+
+            // Two full cycles
+            for _ in 0..(SAMPLE_RATE as f32 * 2.0 / frequency) as usize {
+                Module::<SAMPLE_RATE>::process(&mut naive, &mut patchbay);
+                Module::<SAMPLE_RATE>::process(&mut antialiased, &mut patchbay);
+
+                let value = patchbay.get(antialiased.output());
+                max = max.max(value.abs());
+                difference = difference.max((value - patchbay.get(naive.output())).abs());
+            }
+
+            assert!(
+                max > 0.78 && max < 0.81,
+                "peak of {} at {}Hz",
+                max,
+                frequency
+            );
+
+            // Straight segments at low rates, only the corners are rounded at high rates
+            if frequency <= 20.0 {
+                assert!(
+                    difference < 0.02,
+                    "off by {} at {}Hz",
+                    difference,
+                    frequency
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn antialiased_saw_should_reduce_aliasing() {
+        let naive = aliasing(false, Oscillator::output_saw);
+        let antialiased = aliasing(true, Oscillator::output_saw);
+
+        assert!(antialiased < naive * 0.25);
+    }
+
+    #[test]
+    fn antialiased_pulse_should_reduce_aliasing() {
+        let naive = aliasing(false, |osc| osc.output_pulse(0.3));
+        let antialiased = aliasing(true, |osc| osc.output_pulse(0.3));
+
+        assert!(antialiased < naive * 0.25);
+    }
+
+    #[test]
+    fn oscillator_should_restart_on_a_sync_edge() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut master = Oscillator::new(patchbay.point().unwrap());
+        let mut slave = Oscillator::new(patchbay.point().unwrap());
+
+        // The pulse rises as the phase of the master crosses zero
+        master
+            .set_frequency(100.0)
+            .set_amplitude(1.0)
+            .output_pulse(0.5);
+        slave.set_frequency(330.0).set_sync(master.output());
+
+        let mut previous = 0.0;
+        let mut restarts = 0;
+
+        for _ in 0..SAMPLE_RATE / 10 {
+            Module::<SAMPLE_RATE>::process(&mut master, &mut patchbay);
+
+            assert!(Module::<SAMPLE_RATE>::is_ready(&slave, &patchbay));
+            Module::<SAMPLE_RATE>::process(&mut slave, &mut patchbay);
+
+            let edge = patchbay.get(master.output()) >= 0.5 && previous < 0.5;
+            previous = patchbay.get(master.output());
+
+            if edge {
+                assert_eq!(slave.value, 0.0);
+                restarts += 1;
+            } else {
+                assert!(slave.value != 0.0);
+            }
+        }
+
+        // A restart for every cycle of the master
+        assert_eq!(restarts, 10);
+    }
+
+    // Run a carrier modulated by a sine at twice its frequency, returns the modulated and the
+    // unmodulated carrier
+    fn modulated(fm: bool) -> ([f32; 2400], [f32; 2400]) {
+        let mut patchbay: Patchbay<3> = Patchbay::new();
+        let mut modulator = Oscillator::new(patchbay.point().unwrap());
+        let mut carrier = Oscillator::new(patchbay.point().unwrap());
+        let mut reference = Oscillator::new(patchbay.point().unwrap());
+        let mut output = ([0.0; 2400], [0.0; 2400]);
+
+        modulator.set_frequency(400.0).set_amplitude(1.0);
+        carrier.set_frequency(200.0);
+        reference.set_frequency(200.0);
+
+        if fm {
+            carrier.set_fm(modulator.output(), 200.0);
+        } else {
+            carrier.set_pm(modulator.output(), 0.25);
+        }
+
+        for i in 0..2400 {
+            Module::<SAMPLE_RATE>::process(&mut modulator, &mut patchbay);
+
+            assert!(Module::<SAMPLE_RATE>::is_ready(&carrier, &patchbay));
+            Module::<SAMPLE_RATE>::process(&mut carrier, &mut patchbay);
+            Module::<SAMPLE_RATE>::process(&mut reference, &mut patchbay);
+
+            output.0[i] = patchbay.get(carrier.output());
+            output.1[i] = patchbay.get(reference.output());
+        }
+
+        output
+    }
+
+    #[test]
+    fn oscillator_should_repeat_with_a_harmonic_modulator() {
+        for fm in [true, false] {
+            let (output, reference) = modulated(fm);
+
+            // Repeating every 240 samples, the period of the carrier
+            for i in 0..2400 - 240 {
+                assert!((output[i] - output[i + 240]).abs() < 0.01);
+            }
+
+            // While changing the shape of the carrier
+            let difference = output
+                .iter()
+                .zip(reference.iter())
+                .fold(0.0_f32, |max, (a, b)| max.max((a - b).abs()));
+
+            assert!(difference > 0.2);
+        }
+    }
+
+    #[test]
+    fn oscillator_should_follow_the_tempo_of_a_clock() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut clock = crate::modules::Clock::new(patchbay.point().unwrap(), 120.0);
+        let mut lfo = Oscillator::new(patchbay.point().unwrap());
+
+        lfo.set_frequency(100.0)
+            .set_clock(clock.output())
+            .set_clock_division(1, 4);
+
+        // Runs at the set frequency until the period has been measured over two pulses
+        for _ in 0..48_001 {
+            Module::<SAMPLE_RATE>::process(&mut clock, &mut patchbay);
+            Module::<SAMPLE_RATE>::process(&mut lfo, &mut patchbay);
+        }
+
+        // A cycle every four pulses of 48000 samples each, take the cycle from the start
+        lfo.value = 0.0;
+        let mut cycles = 0;
+
+        for _ in 0..48_000 * 8 {
+            let previous = lfo.value;
+
+            Module::<SAMPLE_RATE>::process(&mut clock, &mut patchbay);
+            Module::<SAMPLE_RATE>::process(&mut lfo, &mut patchbay);
+
+            if lfo.value < previous {
+                cycles += 1;
+            }
+        }
+
+        assert!((lfo.current_frequency - 0.25).abs() < 0.001);
+        assert_eq!(cycles, 2);
+    }
+
+    #[test]
+    fn oscillator_should_shift_the_output_by_the_offset() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut bipolar = Oscillator::new(patchbay.point().unwrap());
+        let mut unipolar = Oscillator::new(patchbay.point().unwrap());
+
+        bipolar.set_frequency(100.0).set_amplitude(0.5);
+        unipolar
+            .set_frequency(100.0)
+            .set_amplitude(0.5)
+            .set_offset(0.5);
+
+        let (mut min, mut max) = (f32::MAX, f32::MIN);
+
+        for _ in 0..SAMPLE_RATE / 100 {
+            Module::<SAMPLE_RATE>::process(&mut bipolar, &mut patchbay);
+            Module::<SAMPLE_RATE>::process(&mut unipolar, &mut patchbay);
+
+            let shifted = patchbay.get(unipolar.output());
+
+            // Same shape, only moved up
+            assert!((shifted - patchbay.get(bipolar.output()) - 0.5).abs() < 1e-6);
+
+            min = min.min(shifted);
+            max = max.max(shifted);
+        }
+
+        assert!((0.0..0.01).contains(&min));
+        assert!(max > 0.99 && max <= 1.0);
+    }
+
+    #[test]
+    fn oscillator_sub_should_be_disconnected_by_default() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let osc = Oscillator::new(patchbay.point().unwrap());
+
+        assert!(matches!(osc.sub(), Signal::None));
+    }
+}
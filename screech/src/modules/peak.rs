@@ -0,0 +1,143 @@
+use super::biquad::Biquad;
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Parametric peaking (bell) EQ.
+pub struct Peak {
+    input: Signal,
+    output: PatchPoint,
+    frequency: f32,
+    q: f32,
+    gain_db: f32,
+    filter: Biquad,
+    update: bool,
+}
+
+impl Peak {
+    pub fn new(output: PatchPoint) -> Self {
+        Peak {
+            input: Signal::None,
+            output,
+            frequency: 1000.0,
+            q: 0.707,
+            gain_db: 0.0,
+            filter: Biquad::new(),
+            update: true,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    /// Set the center frequency of the bell in Hz.
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.frequency = frequency;
+        self.update = true;
+        self
+    }
+
+    pub fn get_frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    /// Set the width of the bell, higher values are narrower.
+    pub fn set_q(&mut self, q: f32) -> &mut Self {
+        self.q = q;
+        self.update = true;
+        self
+    }
+
+    pub fn get_q(&self) -> f32 {
+        self.q
+    }
+
+    /// Set the boost (positive) or cut (negative) at the center frequency in decibels.
+    pub fn set_gain_db(&mut self, gain_db: f32) -> &mut Self {
+        self.gain_db = gain_db;
+        self.update = true;
+        self
+    }
+
+    pub fn get_gain_db(&self) -> f32 {
+        self.gain_db
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Peak {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        // Only recalculate the coefficients when a parameter changed
+        if self.update {
+            self.filter
+                .set_peak(SAMPLE_RATE, self.frequency, self.q, self.gain_db);
+            self.update = false;
+        }
+
+        let output = self.filter.process(patchbay.get(self.input));
+        patchbay.set(&mut self.output, output);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.filter.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math;
+    use core::f32::consts::PI;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    fn gain(gain_db: f32, frequency: f32) -> f32 {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut peak = Peak::new(patchbay.point().unwrap());
+
+        peak.set_input(input.signal())
+            .set_frequency(1000.0)
+            .set_q(2.0)
+            .set_gain_db(gain_db);
+
+        let mut input_energy = 0.0;
+        let mut output_energy = 0.0;
+
+        for i in 0..SAMPLE_RATE / 2 {
+            let sample = math::sin(2.0 * PI * frequency * i as f32 / SAMPLE_RATE as f32);
+            patchbay.set(&mut input, sample);
+            Module::<SAMPLE_RATE>::process(&mut peak, &mut patchbay);
+
+            // Skip the settling time of the filter
+            if i > SAMPLE_RATE / 10 {
+                let output = patchbay.get(peak.output());
+                input_energy += sample * sample;
+                output_energy += output * output;
+            }
+        }
+
+        math::sqrt(output_energy / input_energy)
+    }
+
+    #[test]
+    fn peak_should_apply_the_gain_at_the_center_frequency() {
+        // +12dB
+        assert!((gain(12.0, 1000.0) - 3.981).abs() < 0.05);
+        // -6dB
+        assert!((gain(-6.0, 1000.0) - 0.501).abs() < 0.02);
+    }
+
+    #[test]
+    fn peak_should_leave_distant_frequencies_unaffected() {
+        assert!((gain(12.0, 50.0) - 1.0).abs() < 0.02);
+        assert!((gain(12.0, 15_000.0) - 1.0).abs() < 0.02);
+    }
+}
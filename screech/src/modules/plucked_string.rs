@@ -0,0 +1,182 @@
+use crate::random::Random;
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Karplus-Strong plucked string.
+///
+/// On every rising edge of the trigger a delay line tuned to the set frequency is filled with a
+/// burst of noise, which is then fed back through a low pass filter. `MAX` sets the length of
+/// the delay line in samples and with that the lowest playable frequency (`SAMPLE_RATE / MAX`).
+pub struct PluckedString<const MAX: usize> {
+    trigger: Signal,
+    previous_trigger: f32,
+    output: PatchPoint,
+    frequency: f32,
+    damping: f32,
+    buffer: [f32; MAX],
+    length: usize,
+    position: usize,
+    random: Random,
+}
+
+impl<const MAX: usize> PluckedString<MAX> {
+    pub fn new(trigger: Signal, output: PatchPoint) -> Self {
+        PluckedString {
+            trigger,
+            previous_trigger: 0.0,
+            output,
+            frequency: 220.0,
+            damping: 0.5,
+            buffer: [0.0; MAX],
+            length: MAX.max(1),
+            position: 0,
+            random: Random::new(1),
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    /// Set the frequency used on the next pluck.
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.frequency = frequency;
+        self
+    }
+
+    pub fn get_frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    /// Set the amount of low pass filtering in the feedback path between 0.0 and 1.0, higher
+    /// values make the string sound darker and decay faster.
+    pub fn set_damping(&mut self, damping: f32) -> &mut Self {
+        self.damping = damping.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn get_damping(&self) -> f32 {
+        self.damping
+    }
+
+    /// Seed the noise used to excite the string.
+    pub fn set_seed(&mut self, seed: u32) -> &mut Self {
+        self.random = Random::new(seed);
+        self
+    }
+
+    fn pluck<const SAMPLE_RATE: usize>(&mut self) {
+        // The averaging filter adds a delay of `damping / 2` samples
+        let period = SAMPLE_RATE as f32 / self.frequency.max(1.0) - self.damping * 0.5;
+
+        self.length = (period + 0.5).clamp(2.0, MAX as f32) as usize;
+        self.position = 0;
+
+        for sample in self.buffer[..self.length].iter_mut() {
+            *sample = self.random.next_bipolar();
+        }
+    }
+}
+
+impl<const SAMPLE_RATE: usize, const MAX: usize> Module<SAMPLE_RATE> for PluckedString<MAX> {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.trigger)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let trigger = patchbay.get(self.trigger);
+
+        if trigger >= 0.5 && self.previous_trigger < 0.5 && MAX >= 2 {
+            self.pluck::<SAMPLE_RATE>();
+        }
+
+        self.previous_trigger = trigger;
+
+        if self.length > MAX {
+            patchbay.set(&mut self.output, 0.0);
+            return;
+        }
+
+        let next_position = (self.position + 1) % self.length;
+        let current = self.buffer[self.position];
+        let next = self.buffer[next_position];
+
+        // Low pass filter the feedback with a slight loss so the string always dies out
+        let filtered = current + (next - current) * self.damping * 0.5;
+        self.buffer[self.position] = filtered * 0.999;
+        self.position = next_position;
+
+        patchbay.set(&mut self.output, current);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.previous_trigger = 0.0;
+        self.buffer = [0.0; MAX];
+        self.position = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+    const LENGTH: usize = SAMPLE_RATE / 2;
+
+    fn pluck(frequency: f32, damping: f32) -> [f32; LENGTH] {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut trigger = patchbay.point().unwrap();
+        let mut string: PluckedString<1024> =
+            PluckedString::new(trigger.signal(), patchbay.point().unwrap());
+        let mut output = [0.0; LENGTH];
+
+        string.set_frequency(frequency).set_damping(damping);
+
+        for (i, sample) in output.iter_mut().enumerate() {
+            patchbay.set(&mut trigger, if i < 10 { 1.0 } else { 0.0 });
+            Module::<SAMPLE_RATE>::process(&mut string, &mut patchbay);
+            *sample = patchbay.get(string.output());
+        }
+
+        output
+    }
+
+    fn energy(samples: &[f32]) -> f32 {
+        samples.iter().map(|s| s * s).sum()
+    }
+
+    #[test]
+    fn plucked_string_should_oscillate_at_the_set_frequency() {
+        let output = pluck(480.0, 0.5);
+        let window = &output[1000..3000];
+
+        // Find the strongest period using autocorrelation
+        let mut best_lag = 0;
+        let mut best = f32::MIN;
+
+        for lag in 50..150 {
+            let correlation: f32 = window[..1800]
+                .iter()
+                .zip(window[lag..].iter())
+                .map(|(a, b)| a * b)
+                .sum();
+
+            if correlation > best {
+                best = correlation;
+                best_lag = lag;
+            }
+        }
+
+        // 48000 / 480 = 100 samples per period
+        assert!((99..=101).contains(&best_lag));
+    }
+
+    #[test]
+    fn plucked_string_should_decay_faster_with_more_damping() {
+        let light = pluck(220.0, 0.1);
+        let heavy = pluck(220.0, 1.0);
+
+        assert!(energy(&light[20_000..24_000]) < energy(&light[..4000]));
+        assert!(energy(&heavy[20_000..24_000]) < energy(&heavy[..4000]));
+        assert!(energy(&heavy[20_000..24_000]) < energy(&light[20_000..24_000]) * 0.5);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{Event, Module, ParamId, PatchPoint, Patchbay, Signal};
+
+/// Polyphonic wrapper playing notes on `N` voices
+///
+/// Every [`Event::NoteOn`] received, e.g. through an [`crate::EventQueue`], is passed on to a
+/// free voice. When all voices are playing the voice holding the oldest note is stolen.
+/// [`Event::NoteOff`] goes to the voice playing that note and [`Event::Choke`] to all voices.
+/// The voices are processed by the wrapper and their outputs summed into a single output.
+pub struct Poly<V, const N: usize> {
+    voices: [V; N],
+    voice_outputs: [Signal; N],
+    output: PatchPoint,
+    notes: [Option<u8>; N],
+    started: [usize; N],
+    note_count: usize,
+}
+
+impl<V, const N: usize> Poly<V, N> {
+    /// Create a wrapper for the voices, where `voice_outputs` holds the output of every voice.
+    pub fn new(output: PatchPoint, voices: [V; N], voice_outputs: [Signal; N]) -> Self {
+        Poly {
+            voices,
+            voice_outputs,
+            output,
+            notes: [None; N],
+            started: [0; N],
+            note_count: 0,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn voices(&self) -> &[V; N] {
+        &self.voices
+    }
+
+    pub fn voices_mut(&mut self) -> &mut [V; N] {
+        &mut self.voices
+    }
+
+    /// Note held by every voice, `None` for free voices.
+    pub fn notes(&self) -> [Option<u8>; N] {
+        self.notes
+    }
+
+    // Least recently started free voice, or the voice holding the oldest note if none are free
+    fn next_voice(&self) -> Option<usize> {
+        (0..N)
+            .filter(|&index| self.notes[index].is_none())
+            .min_by_key(|&index| self.started[index])
+            .or_else(|| (0..N).min_by_key(|&index| self.started[index]))
+    }
+}
+
+impl<const SAMPLE_RATE: usize, V: Module<SAMPLE_RATE>, const N: usize> Module<SAMPLE_RATE>
+    for Poly<V, N>
+{
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        self.voices.iter().all(|voice| voice.is_ready(patchbay))
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let mut sum = 0.0;
+
+        for (voice, output) in self.voices.iter_mut().zip(self.voice_outputs.iter()) {
+            voice.process(patchbay);
+            sum += patchbay.get(*output);
+        }
+
+        patchbay.set(&mut self.output, sum);
+    }
+
+    fn latency(&self) -> usize {
+        self.voices
+            .iter()
+            .map(|voice| voice.latency())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn cost_hint(&self) -> u32 {
+        self.voices.iter().map(|voice| voice.cost_hint()).sum()
+    }
+
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::NoteOn { note, .. } => {
+                if let Some(index) = self.next_voice() {
+                    self.note_count += 1;
+                    self.notes[index] = Some(note);
+                    self.started[index] = self.note_count;
+                    self.voices[index].handle_event(event);
+                }
+            }
+            Event::NoteOff { note } => {
+                if let Some(index) = self.notes.iter().position(|n| *n == Some(note)) {
+                    self.notes[index] = None;
+                    self.voices[index].handle_event(event);
+                }
+            }
+            Event::Choke => {
+                self.notes = [None; N];
+
+                for voice in self.voices.iter_mut() {
+                    voice.handle_event(event);
+                }
+            }
+        }
+    }
+
+    fn reset<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        self.notes = [None; N];
+
+        for voice in self.voices.iter_mut() {
+            voice.reset(patchbay);
+        }
+    }
+
+    fn ramp(&mut self, param: ParamId, target: f32, samples: usize) {
+        for voice in self.voices.iter_mut() {
+            voice.ramp(param, target, samples);
+        }
+    }
+
+    fn advance_ramps(&mut self) {
+        for voice in self.voices.iter_mut() {
+            voice.advance_ramps();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::Oscillator;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    struct Voice {
+        level: f32,
+        output: PatchPoint,
+    }
+
+    impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Voice {
+        fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+            patchbay.set(&mut self.output, self.level);
+        }
+
+        fn handle_event(&mut self, event: Event) {
+            self.level = match event {
+                Event::NoteOn { velocity, .. } => velocity,
+                Event::NoteOff { .. } | Event::Choke => 0.0,
+            };
+        }
+    }
+
+    #[test]
+    fn poly_should_steal_the_voice_playing_the_oldest_note() {
+        let mut patchbay: Patchbay<3> = Patchbay::new();
+        let voices = [
+            Voice {
+                level: 0.0,
+                output: patchbay.point().unwrap(),
+            },
+            Voice {
+                level: 0.0,
+                output: patchbay.point().unwrap(),
+            },
+        ];
+        let voice_outputs = [voices[0].output.signal(), voices[1].output.signal()];
+        let mut poly = Poly::new(patchbay.point().unwrap(), voices, voice_outputs);
+
+        let mut play = |poly: &mut Poly<Voice, 2>, event: Event| {
+            Module::<SAMPLE_RATE>::handle_event(poly, event);
+            Module::<SAMPLE_RATE>::process(poly, &mut patchbay);
+            patchbay.get(poly.output())
+        };
+
+        let note_on = |note, velocity| Event::NoteOn { note, velocity };
+
+        assert_eq!(play(&mut poly, note_on(60, 0.1)), 0.1);
+        assert_eq!(play(&mut poly, note_on(62, 0.2)), 0.1 + 0.2);
+
+        // The oldest note is stolen, the newest notes sound
+        assert_eq!(play(&mut poly, note_on(64, 0.4)), 0.4 + 0.2);
+        assert_eq!(poly.notes(), [Some(64), Some(62)]);
+
+        // Releasing a note frees its voice before anything gets stolen again
+        assert_eq!(play(&mut poly, Event::NoteOff { note: 62 }), 0.4);
+        assert_eq!(play(&mut poly, note_on(65, 0.5)), 0.4 + 0.5);
+        assert_eq!(poly.notes(), [Some(64), Some(65)]);
+
+        // Releasing a note that isn't playing does nothing
+        assert_eq!(play(&mut poly, Event::NoteOff { note: 60 }), 0.4 + 0.5);
+
+        assert_eq!(play(&mut poly, Event::Choke), 0.0);
+        assert_eq!(poly.notes(), [None, None]);
+    }
+
+    #[test]
+    fn poly_should_forward_ramps_to_every_voice() {
+        let mut patchbay: Patchbay<3> = Patchbay::new();
+        let voices = [
+            Oscillator::new(patchbay.point().unwrap()),
+            Oscillator::new(patchbay.point().unwrap()),
+        ];
+        let voice_outputs = [voices[0].output(), voices[1].output()];
+        let mut poly = Poly::new(patchbay.point().unwrap(), voices, voice_outputs);
+
+        Module::<SAMPLE_RATE>::ramp(&mut poly, Oscillator::FREQUENCY, 220.0, 10);
+
+        for _ in 0..10 {
+            Module::<SAMPLE_RATE>::advance_ramps(&mut poly);
+        }
+
+        for voice in poly.voices.iter() {
+            assert_eq!(voice.get_frequency(), 220.0);
+        }
+    }
+}
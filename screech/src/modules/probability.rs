@@ -0,0 +1,134 @@
+use crate::random::Random;
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Randomly lets gates through with a set chance.
+///
+/// On every rising edge of the input a new decision is made, the whole gate is either passed
+/// through or blocked. Use [`Probability::set_seed`] for reproducible sequences.
+pub struct Probability {
+    input: Signal,
+    output: PatchPoint,
+    chance: f32,
+    previous_input: f32,
+    open: bool,
+    random: Random,
+}
+
+impl Probability {
+    pub fn new(output: PatchPoint) -> Self {
+        Probability {
+            input: Signal::None,
+            output,
+            chance: 0.5,
+            previous_input: 0.0,
+            open: false,
+            random: Random::new(1),
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    /// Set the chance of a gate passing through between 0.0 (never) and 1.0 (always).
+    pub fn set_chance(&mut self, chance: f32) -> &mut Self {
+        self.chance = chance.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn get_chance(&self) -> f32 {
+        self.chance
+    }
+
+    pub fn set_seed(&mut self, seed: u32) -> &mut Self {
+        self.random = Random::new(seed);
+        self
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Probability {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let input = patchbay.get(self.input);
+
+        if input >= 0.5 && self.previous_input < 0.5 {
+            self.open = self.random.next_f32() < self.chance;
+        }
+
+        self.previous_input = input;
+
+        let output = if self.open && input >= 0.5 { 1.0 } else { 0.0 };
+
+        patchbay.set(&mut self.output, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    // Feed 32 gates and collect which ones passed
+    fn gates(chance: f32, seed: u32) -> [bool; 32] {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut probability = Probability::new(patchbay.point().unwrap());
+        let mut passed = [false; 32];
+
+        probability
+            .set_input(input.signal())
+            .set_chance(chance)
+            .set_seed(seed);
+
+        for gate in passed.iter_mut() {
+            for i in 0..4 {
+                patchbay.set(&mut input, if i < 2 { 1.0 } else { 0.0 });
+                Module::<SAMPLE_RATE>::process(&mut probability, &mut patchbay);
+
+                let output = patchbay.get(probability.output());
+
+                match i {
+                    0 => *gate = output == 1.0,
+                    // The whole gate is passed or blocked
+                    1 => assert_eq!(output == 1.0, *gate),
+                    _ => assert_eq!(output, 0.0),
+                }
+            }
+        }
+
+        passed
+    }
+
+    #[test]
+    fn probability_should_always_pass_with_full_chance() {
+        assert_eq!(gates(1.0, 3), [true; 32]);
+    }
+
+    #[test]
+    fn probability_should_never_pass_without_chance() {
+        assert_eq!(gates(0.0, 3), [false; 32]);
+    }
+
+    #[test]
+    fn probability_should_be_deterministic_for_a_seed() {
+        let passed = gates(0.5, 42);
+        let mut random = Random::new(42);
+
+        for &gate in passed.iter() {
+            assert_eq!(gate, random.next_f32() < 0.5);
+        }
+
+        assert_eq!(passed, gates(0.5, 42));
+        assert_ne!(passed, gates(0.5, 43));
+        assert!(passed.iter().any(|&p| p) && passed.iter().any(|&p| !p));
+    }
+}
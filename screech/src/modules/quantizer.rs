@@ -0,0 +1,119 @@
+use crate::pitch::Scale;
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Snaps a pitch CV to the nearest note of a scale
+///
+/// The input and output use 1.0 per octave, so 1.0 / 12.0 per semitone, with 0.0 as the root of
+/// the scale.
+pub struct Quantizer {
+    input: Signal,
+    output: PatchPoint,
+    scale: Scale,
+}
+
+impl Quantizer {
+    pub fn new(output: PatchPoint) -> Self {
+        Quantizer {
+            input: Signal::None,
+            output,
+            scale: Scale::CHROMATIC,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    /// Set the scale as semitones above the root, e.g. `&[0, 2, 4, 5, 7, 9, 11]` for major.
+    /// Defaults to all twelve semitones.
+    pub fn set_scale(&mut self, semitones: &[u8]) -> &mut Self {
+        self.scale = Scale::new(semitones);
+        self
+    }
+
+    pub fn get_scale(&self) -> Scale {
+        self.scale
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Quantizer {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let semitones = patchbay.get(self.input) * 12.0;
+
+        patchbay.set(&mut self.output, self.scale.quantize(semitones) / 12.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn quantizer_should_step_a_ramp_through_a_major_scale() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut quantizer = Quantizer::new(patchbay.point().unwrap());
+
+        quantizer
+            .set_input(input.signal())
+            .set_scale(&[0, 2, 4, 5, 7, 9, 11]);
+
+        assert_eq!(quantizer.get_scale(), Scale::MAJOR);
+
+        // Ramp through an octave in 1200 samples, 100 samples per semitone
+        let mut steps = [0.0; 1200];
+
+        for (i, step) in steps.iter_mut().enumerate() {
+            patchbay.set(&mut input, i as f32 / 1200.0);
+            Module::<SAMPLE_RATE>::process(&mut quantizer, &mut patchbay);
+            *step = patchbay.get(quantizer.output()) * 12.0;
+        }
+
+        // Notes outside the scale sit halfway between two notes and round down on the tie
+        let expected = [
+            (0, 0.0),
+            (99, 0.0),
+            (100, 0.0),
+            (101, 2.0),
+            (300, 2.0),
+            (301, 4.0),
+            (449, 4.0),
+            (451, 5.0),
+            (600, 5.0),
+            (601, 7.0),
+            (800, 7.0),
+            (801, 9.0),
+            (1000, 9.0),
+            (1001, 11.0),
+            (1150, 11.0),
+            (1151, 12.0),
+            (1199, 12.0),
+        ];
+
+        for (i, semitones) in expected {
+            assert!(
+                (steps[i] - semitones).abs() < 1e-4,
+                "expected {} at sample {}, got {}",
+                semitones,
+                i,
+                steps[i]
+            );
+        }
+
+        // Only rises in steps
+        for pair in steps.windows(2) {
+            assert!(pair[1] >= pair[0]);
+        }
+    }
+}
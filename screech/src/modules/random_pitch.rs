@@ -0,0 +1,143 @@
+use crate::pitch::Scale;
+use crate::random::Random;
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Random melody generator
+///
+/// On every rising edge of the clock a random pitch within the range is picked, quantized to
+/// the scale and held until the next edge. The output is a pitch in octaves above the root of
+/// the scale, so 1.0 per octave and 1.0 / 12.0 per semitone.
+pub struct RandomPitch {
+    clock: Signal,
+    output: PatchPoint,
+    scale: Scale,
+    range: f32,
+    previous_clock: f32,
+    value: f32,
+    random: Random,
+}
+
+impl RandomPitch {
+    pub fn new(output: PatchPoint) -> Self {
+        RandomPitch {
+            clock: Signal::None,
+            output,
+            scale: Scale::MAJOR,
+            range: 1.0,
+            previous_clock: 0.0,
+            value: 0.0,
+            random: Random::new(1),
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_clock(&mut self, signal: Signal) -> &mut Self {
+        self.clock = signal;
+        self
+    }
+
+    /// Set the scale pitches are quantized to, defaults to [`Scale::MAJOR`].
+    pub fn set_scale(&mut self, scale: Scale) -> &mut Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn get_scale(&self) -> Scale {
+        self.scale
+    }
+
+    /// Set the range pitches are picked from in octaves above the root, defaults to 1.0.
+    pub fn set_range(&mut self, octaves: f32) -> &mut Self {
+        self.range = octaves.max(0.0);
+        self
+    }
+
+    pub fn get_range(&self) -> f32 {
+        self.range
+    }
+
+    pub fn set_seed(&mut self, seed: u32) -> &mut Self {
+        self.random = Random::new(seed);
+        self
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for RandomPitch {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.clock)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let clock = patchbay.get(self.clock);
+
+        if clock >= 0.5 && self.previous_clock < 0.5 {
+            let semitones = self.random.next_f32() * self.range * 12.0;
+            self.value = self.scale.quantize(semitones) / 12.0;
+        }
+
+        self.previous_clock = clock;
+
+        patchbay.set(&mut self.output, self.value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    // Pitches during 16 clock cycles of 100 samples, asserting they only change on clock edges
+    fn melody(seed: u32) -> [f32; 16] {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut clock = patchbay.point().unwrap();
+        let mut random_pitch = RandomPitch::new(patchbay.point().unwrap());
+        let mut pitches = [0.0; 16];
+
+        random_pitch
+            .set_clock(clock.signal())
+            .set_scale(Scale::MINOR_PENTATONIC)
+            .set_range(2.0)
+            .set_seed(seed);
+
+        for (cycle, pitch) in pitches.iter_mut().enumerate() {
+            for i in 0..100 {
+                patchbay.set(&mut clock, if i < 50 { 1.0 } else { 0.0 });
+                Module::<SAMPLE_RATE>::process(&mut random_pitch, &mut patchbay);
+
+                let value = patchbay.get(random_pitch.output());
+
+                if i == 0 {
+                    *pitch = value;
+                } else {
+                    assert_eq!(value, *pitch, "changed in between edges of cycle {}", cycle);
+                }
+            }
+        }
+
+        pitches
+    }
+
+    #[test]
+    fn random_pitch_should_pick_scale_degrees_on_clock_edges() {
+        let pitches = melody(7);
+
+        assert_eq!(pitches, melody(7));
+        assert_ne!(pitches, melody(8));
+
+        for pitch in pitches {
+            let semitones = pitch * 12.0;
+            let degree = (semitones as i32).rem_euclid(12) as u8;
+
+            assert!((semitones - semitones.round()).abs() < 1e-4);
+            assert!(Scale::MINOR_PENTATONIC.contains(degree));
+            assert!((0.0..=2.0).contains(&pitch));
+        }
+
+        // Not stuck on a single pitch
+        assert!(pitches.iter().any(|p| *p != pitches[0]));
+    }
+}
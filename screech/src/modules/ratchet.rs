@@ -0,0 +1,155 @@
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Splits every clock pulse into a burst of evenly spaced sub pulses.
+///
+/// The clock period is measured between the last two rising edges, the sub pulses are spread
+/// across that period with a 50% duty cycle. Until two edges have been seen, or when the clock
+/// stops, the output follows the clock input.
+pub struct Ratchet {
+    clock: Signal,
+    output: PatchPoint,
+    ratchets: usize,
+    previous_clock: f32,
+    period: Option<usize>,
+    elapsed: usize,
+    started: bool,
+}
+
+impl Ratchet {
+    pub fn new(clock: Signal, output: PatchPoint) -> Self {
+        Ratchet {
+            clock,
+            output,
+            ratchets: 2,
+            previous_clock: 0.0,
+            period: None,
+            elapsed: 0,
+            started: false,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    /// Set the amount of sub pulses for each clock pulse.
+    pub fn set_ratchets(&mut self, ratchets: usize) -> &mut Self {
+        self.ratchets = ratchets.max(1);
+        self
+    }
+
+    pub fn get_ratchets(&self) -> usize {
+        self.ratchets
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Ratchet {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.clock)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let clock = patchbay.get(self.clock);
+
+        if clock >= 0.5 && self.previous_clock < 0.5 {
+            if self.started {
+                self.period = Some(self.elapsed);
+            }
+
+            self.started = true;
+            self.elapsed = 0;
+        }
+
+        self.previous_clock = clock;
+
+        let output = match self.period {
+            Some(period) if self.elapsed < period => {
+                let sub_period = period as f32 / self.ratchets as f32;
+                let position = self.elapsed as f32 % sub_period;
+
+                if position < sub_period * 0.5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            _ => {
+                if clock >= 0.5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        };
+
+        self.elapsed = self.elapsed.saturating_add(1);
+
+        patchbay.set(&mut self.output, output);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.previous_clock = 0.0;
+        self.period = None;
+        self.elapsed = 0;
+        self.started = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn ratchet_should_split_a_clock_period_into_even_sub_pulses() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut clock = patchbay.point().unwrap();
+        let mut ratchet = Ratchet::new(clock.signal(), patchbay.point().unwrap());
+
+        ratchet.set_ratchets(3);
+
+        let mut edges = [0; 16];
+        let mut count = 0;
+        let mut previous = 0.0;
+
+        // A clock with a period of 1200 samples
+        for i in 0..1200 * 4 {
+            patchbay.set(&mut clock, if i % 1200 < 600 { 1.0 } else { 0.0 });
+            Module::<SAMPLE_RATE>::process(&mut ratchet, &mut patchbay);
+
+            let output = patchbay.get(ratchet.output());
+
+            // Only look at the periods after the first one has been measured
+            if output > previous && i >= 1200 {
+                edges[count] = i;
+                count += 1;
+            }
+
+            previous = output;
+        }
+
+        assert_eq!(count, 9);
+        assert_eq!(
+            &edges[..count],
+            &[1200, 1600, 2000, 2400, 2800, 3200, 3600, 4000, 4400]
+        );
+    }
+
+    #[test]
+    fn ratchet_should_follow_the_clock_until_a_period_is_known() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut clock = patchbay.point().unwrap();
+        let mut ratchet = Ratchet::new(clock.signal(), patchbay.point().unwrap());
+
+        ratchet.set_ratchets(4);
+
+        for i in 0..1200 {
+            let value = if i < 600 { 1.0 } else { 0.0 };
+            patchbay.set(&mut clock, value);
+            Module::<SAMPLE_RATE>::process(&mut ratchet, &mut patchbay);
+
+            assert_eq!(patchbay.get(ratchet.output()), value);
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use crate::{math, Module, PatchPoint, Patchbay, Signal};
+use core::f32::consts::PI;
+
+#[derive(Copy, Clone)]
+struct Mode {
+    frequency: f32,
+    decay: f32,
+    gain: f32,
+    b0: f32,
+    a1: f32,
+    a2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl Mode {
+    fn new() -> Self {
+        Mode {
+            frequency: 0.0,
+            decay: 0.0,
+            gain: 0.0,
+            b0: 0.0,
+            a1: 0.0,
+            a2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        }
+    }
+
+    fn update(&mut self, sample_rate: usize) {
+        let nyquist = sample_rate as f32 * 0.5;
+        let w = 2.0 * PI * self.frequency.clamp(0.0, nyquist * 0.99) / sample_rate as f32;
+
+        // Pole radius giving a 60dB decay over `decay` seconds
+        let r = if self.decay > 0.0 {
+            math::exp(-6.907_755 / (self.decay * sample_rate as f32))
+        } else {
+            0.0
+        };
+
+        self.a1 = 2.0 * r * math::cos(w);
+        self.a2 = -r * r;
+        // Scale so an impulse of 1.0 rings with an amplitude of `gain`
+        self.b0 = self.gain * math::sin(w);
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let output = self.b0 * input + self.a1 * self.y1 + self.a2 * self.y2;
+
+        self.y2 = self.y1;
+        self.y1 = output;
+
+        output
+    }
+
+    fn clear(&mut self) {
+        self.y1 = 0.0;
+        self.y2 = 0.0;
+    }
+}
+
+/// Bank of tuned resonators for modal synthesis.
+///
+/// Each mode rings at its own frequency when excited by the input, for example an impulse or a
+/// short burst of noise, and decays by 60dB over its decay time in seconds.
+pub struct Resonator<const MODES: usize> {
+    input: Signal,
+    output: PatchPoint,
+    modes: [Mode; MODES],
+    update: bool,
+}
+
+impl<const MODES: usize> Resonator<MODES> {
+    pub fn new(output: PatchPoint) -> Self {
+        Resonator {
+            input: Signal::None,
+            output,
+            modes: [Mode::new(); MODES],
+            update: true,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    /// Configure a single mode, modes with a gain of `0.0` are silent.
+    pub fn set_mode(&mut self, index: usize, frequency: f32, decay: f32, gain: f32) -> &mut Self {
+        let mode = &mut self.modes[index];
+
+        mode.frequency = frequency;
+        mode.decay = decay;
+        mode.gain = gain;
+
+        self.update = true;
+        self
+    }
+
+    /// Get the frequency, decay and gain of a mode.
+    pub fn get_mode(&self, index: usize) -> (f32, f32, f32) {
+        let mode = &self.modes[index];
+        (mode.frequency, mode.decay, mode.gain)
+    }
+}
+
+impl<const SAMPLE_RATE: usize, const MODES: usize> Module<SAMPLE_RATE> for Resonator<MODES> {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        // Only recalculate the coefficients when a mode changed
+        if self.update {
+            for mode in self.modes.iter_mut() {
+                mode.update(SAMPLE_RATE);
+            }
+
+            self.update = false;
+        }
+
+        let input = patchbay.get(self.input);
+        let mut output = 0.0;
+
+        for mode in self.modes.iter_mut() {
+            output += mode.process(input);
+        }
+
+        patchbay.set(&mut self.output, output);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        for mode in self.modes.iter_mut() {
+            mode.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+    const LENGTH: usize = SAMPLE_RATE / 5;
+
+    fn ring(resonator: &mut Resonator<2>, patchbay: &mut Patchbay<2>) -> [f32; LENGTH] {
+        let mut input = patchbay.point().unwrap();
+        let mut output = [0.0; LENGTH];
+
+        resonator.set_input(input.signal());
+
+        for (i, sample) in output.iter_mut().enumerate() {
+            // Impulse
+            patchbay.set(&mut input, if i == 0 { 1.0 } else { 0.0 });
+            Module::<SAMPLE_RATE>::process(resonator, patchbay);
+            *sample = patchbay.get(resonator.output());
+        }
+
+        output
+    }
+
+    fn magnitude(samples: &[f32], frequency: f32) -> f32 {
+        let (mut real, mut imaginary) = (0.0, 0.0);
+
+        for (i, sample) in samples.iter().enumerate() {
+            let phase = 2.0 * PI * frequency * i as f32 / SAMPLE_RATE as f32;
+            real += sample * math::cos(phase);
+            imaginary += sample * math::sin(phase);
+        }
+
+        math::sqrt(real * real + imaginary * imaginary)
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0, |peak, s| s.abs().max(peak))
+    }
+
+    #[test]
+    fn resonator_should_ring_at_the_mode_frequencies() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut resonator: Resonator<2> = Resonator::new(patchbay.point().unwrap());
+
+        resonator
+            .set_mode(0, 500.0, 0.5, 1.0)
+            .set_mode(1, 1200.0, 0.5, 0.5);
+
+        let output = ring(&mut resonator, &mut patchbay);
+        let window = &output[..4800];
+
+        let first = magnitude(window, 500.0);
+        let second = magnitude(window, 1200.0);
+        let between = magnitude(window, 800.0);
+
+        assert!(first > between * 20.0);
+        assert!(second > between * 10.0);
+        assert!(first > second);
+    }
+
+    #[test]
+    fn resonator_should_decay_over_the_set_time() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut resonator: Resonator<2> = Resonator::new(patchbay.point().unwrap());
+
+        resonator.set_mode(0, 1000.0, 0.1, 1.0);
+
+        let output = ring(&mut resonator, &mut patchbay);
+
+        let start = peak(&output[..480]);
+        // Around the 100ms decay time
+        let end = peak(&output[4560..5040]);
+
+        assert!((start - 1.0).abs() < 0.05);
+        assert!(end < start * 0.002);
+        assert!(end > start * 0.0005);
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Mutes the output when the input runs away, protecting ears and speakers from blown up
+/// feedback loops.
+///
+/// Once the input level stays above the threshold for longer than the set number of samples
+/// the output fades to silence and stays muted until [`SafetyLimiter::rearm`] is called or the module is reset.
+/// Non finite input (`inf`, `NaN`) always counts as being over the threshold and is never
+/// passed through.
+pub struct SafetyLimiter {
+    input: Signal,
+    output: PatchPoint,
+    threshold: f32,
+    hold: usize,
+    fade: f32,
+    over: usize,
+    gain: f32,
+    tripped: bool,
+}
+
+impl SafetyLimiter {
+    pub fn new(output: PatchPoint) -> Self {
+        SafetyLimiter {
+            input: Signal::None,
+            output,
+            threshold: 2.0,
+            hold: 64,
+            fade: 0.01,
+            over: 0,
+            gain: 1.0,
+            tripped: false,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    /// Absolute level the input has to exceed, defaults to 2.0.
+    pub fn set_threshold(&mut self, threshold: f32) -> &mut Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn get_threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    /// Number of consecutive samples over the threshold before muting, defaults to 64.
+    pub fn set_hold(&mut self, samples: usize) -> &mut Self {
+        self.hold = samples;
+        self
+    }
+
+    pub fn get_hold(&self) -> usize {
+        self.hold
+    }
+
+    /// Time in seconds to fade the output out once tripped, defaults to 10ms.
+    pub fn set_fade(&mut self, seconds: f32) -> &mut Self {
+        self.fade = seconds;
+        self
+    }
+
+    pub fn get_fade(&self) -> f32 {
+        self.fade
+    }
+
+    pub fn is_tripped(&self) -> bool {
+        self.tripped
+    }
+
+    /// Unmute the output after it has been tripped.
+    pub fn rearm(&mut self) -> &mut Self {
+        self.tripped = false;
+        self.over = 0;
+        self.gain = 1.0;
+        self
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for SafetyLimiter {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let input = patchbay.get(self.input);
+        let finite = input.is_finite();
+
+        if !finite || input.abs() > self.threshold {
+            self.over += 1;
+        } else {
+            self.over = 0;
+        }
+
+        if self.over > self.hold {
+            self.tripped = true;
+        }
+
+        if self.tripped {
+            let fade_samples = self.fade * SAMPLE_RATE as f32;
+
+            if fade_samples > 1.0 {
+                self.gain = (self.gain - 1.0 / fade_samples).max(0.0);
+            } else {
+                self.gain = 0.0;
+            }
+        }
+
+        let output = if finite { input * self.gain } else { 0.0 };
+
+        patchbay.set(&mut self.output, output);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.rearm();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn safety_limiter_should_mute_a_runaway_signal_until_reset() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut limiter = SafetyLimiter::new(patchbay.point().unwrap());
+
+        // 48 samples of fade at 48kHz
+        limiter
+            .set_input(input.signal())
+            .set_threshold(1.0)
+            .set_hold(10)
+            .set_fade(0.001);
+
+        // Ramp past the threshold at sample 100
+        for i in 0..1000 {
+            let value = i as f32 * 0.01;
+            patchbay.set(&mut input, value);
+            Module::<SAMPLE_RATE>::process(&mut limiter, &mut patchbay);
+
+            let output = patchbay.get(limiter.output());
+
+            if i <= 110 {
+                assert!(!limiter.is_tripped());
+                assert_eq!(output, value);
+            } else if i < 111 + 48 {
+                // Fading out instead of cutting off
+                assert!(limiter.is_tripped());
+                assert!(output > 0.0 && output < value);
+            } else {
+                assert_eq!(output, 0.0);
+            }
+        }
+
+        // Staying muted with a sane input
+        patchbay.set(&mut input, 0.5);
+
+        for _ in 0..100 {
+            Module::<SAMPLE_RATE>::process(&mut limiter, &mut patchbay);
+            assert_eq!(patchbay.get(limiter.output()), 0.0);
+        }
+
+        limiter.rearm();
+        Module::<SAMPLE_RATE>::process(&mut limiter, &mut patchbay);
+
+        assert!(!limiter.is_tripped());
+        assert_eq!(patchbay.get(limiter.output()), 0.5);
+    }
+
+    #[test]
+    fn safety_limiter_should_unmute_on_module_reset() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut limiter = SafetyLimiter::new(patchbay.point().unwrap());
+
+        limiter.set_input(Signal::Fixed(f32::NAN)).set_hold(0);
+        Module::<SAMPLE_RATE>::process(&mut limiter, &mut patchbay);
+        assert!(limiter.is_tripped());
+
+        Module::<SAMPLE_RATE>::reset(&mut limiter, &mut patchbay);
+        limiter.set_input(Signal::Fixed(0.5));
+        Module::<SAMPLE_RATE>::process(&mut limiter, &mut patchbay);
+
+        assert!(!limiter.is_tripped());
+        assert_eq!(patchbay.get(limiter.output()), 0.5);
+    }
+
+    #[test]
+    fn safety_limiter_should_never_pass_non_finite_values() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut limiter = SafetyLimiter::new(patchbay.point().unwrap());
+
+        limiter.set_input(Signal::Fixed(f32::NAN));
+        Module::<SAMPLE_RATE>::process(&mut limiter, &mut patchbay);
+        assert_eq!(patchbay.get(limiter.output()), 0.0);
+
+        limiter.set_input(Signal::Fixed(f32::INFINITY));
+        Module::<SAMPLE_RATE>::process(&mut limiter, &mut patchbay);
+        assert_eq!(patchbay.get(limiter.output()), 0.0);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Latches the input on every rising edge of the trigger and holds it until the next one.
+pub struct SampleAndHold {
+    input: Signal,
+    trigger: Signal,
+    output: PatchPoint,
+    previous_trigger: f32,
+    value: f32,
+}
+
+impl SampleAndHold {
+    pub fn new(output: PatchPoint) -> Self {
+        SampleAndHold {
+            input: Signal::None,
+            trigger: Signal::None,
+            output,
+            previous_trigger: 0.0,
+            value: 0.0,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    pub fn set_trigger(&mut self, signal: Signal) -> &mut Self {
+        self.trigger = signal;
+        self
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for SampleAndHold {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input) && patchbay.check(self.trigger)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let trigger = patchbay.get(self.trigger);
+
+        if trigger >= 0.5 && self.previous_trigger < 0.5 {
+            self.value = patchbay.get(self.input);
+        }
+
+        self.previous_trigger = trigger;
+
+        patchbay.set(&mut self.output, self.value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn sample_and_hold_should_output_a_staircase() {
+        let mut patchbay: Patchbay<3> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut trigger = patchbay.point().unwrap();
+        let mut sample_and_hold = SampleAndHold::new(patchbay.point().unwrap());
+
+        sample_and_hold
+            .set_input(input.signal())
+            .set_trigger(trigger.signal());
+
+        for i in 0..40 {
+            // Ramp up with a short trigger every 10 samples
+            patchbay.set(&mut input, i as f32 / 40.0);
+            patchbay.set(&mut trigger, if i % 10 < 2 { 1.0 } else { 0.0 });
+
+            Module::<SAMPLE_RATE>::process(&mut sample_and_hold, &mut patchbay);
+
+            let step = (i / 10 * 10) as f32 / 40.0;
+            assert_eq!(patchbay.get(sample_and_hold.output()), step);
+        }
+    }
+}
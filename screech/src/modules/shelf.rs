@@ -0,0 +1,164 @@
+use super::biquad::Biquad;
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Which end of the spectrum a [`Shelf`] boosts or cuts.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ShelfMode {
+    Low,
+    High,
+}
+
+/// Low or high shelving EQ for simple tonal shaping.
+pub struct Shelf {
+    input: Signal,
+    output: PatchPoint,
+    mode: ShelfMode,
+    frequency: f32,
+    gain_db: f32,
+    filter: Biquad,
+    update: bool,
+}
+
+impl Shelf {
+    pub fn new(output: PatchPoint) -> Self {
+        Shelf {
+            input: Signal::None,
+            output,
+            mode: ShelfMode::Low,
+            frequency: 1000.0,
+            gain_db: 0.0,
+            filter: Biquad::new(),
+            update: true,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    pub fn set_mode(&mut self, mode: ShelfMode) -> &mut Self {
+        self.mode = mode;
+        self.update = true;
+        self
+    }
+
+    pub fn get_mode(&self) -> ShelfMode {
+        self.mode
+    }
+
+    /// Set the corner frequency of the shelf in Hz.
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.frequency = frequency;
+        self.update = true;
+        self
+    }
+
+    pub fn get_frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    /// Set the boost (positive) or cut (negative) of the shelf in decibels.
+    pub fn set_gain_db(&mut self, gain_db: f32) -> &mut Self {
+        self.gain_db = gain_db;
+        self.update = true;
+        self
+    }
+
+    pub fn get_gain_db(&self) -> f32 {
+        self.gain_db
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Shelf {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        // Only recalculate the coefficients when a parameter changed
+        if self.update {
+            match self.mode {
+                ShelfMode::Low => {
+                    self.filter
+                        .set_low_shelf(SAMPLE_RATE, self.frequency, self.gain_db)
+                }
+                ShelfMode::High => {
+                    self.filter
+                        .set_high_shelf(SAMPLE_RATE, self.frequency, self.gain_db)
+                }
+            }
+
+            self.update = false;
+        }
+
+        let output = self.filter.process(patchbay.get(self.input));
+        patchbay.set(&mut self.output, output);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.filter.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math;
+    use core::f32::consts::PI;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    fn gain(mode: ShelfMode, gain_db: f32, frequency: f32) -> f32 {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut shelf = Shelf::new(patchbay.point().unwrap());
+
+        shelf
+            .set_input(input.signal())
+            .set_mode(mode)
+            .set_frequency(1000.0)
+            .set_gain_db(gain_db);
+
+        let mut input_energy = 0.0;
+        let mut output_energy = 0.0;
+
+        for i in 0..SAMPLE_RATE / 2 {
+            let sample = math::sin(2.0 * PI * frequency * i as f32 / SAMPLE_RATE as f32);
+            patchbay.set(&mut input, sample);
+            Module::<SAMPLE_RATE>::process(&mut shelf, &mut patchbay);
+
+            // Skip the settling time of the filter
+            if i > SAMPLE_RATE / 10 {
+                let output = patchbay.get(shelf.output());
+                input_energy += sample * sample;
+                output_energy += output * output;
+            }
+        }
+
+        math::sqrt(output_energy / input_energy)
+    }
+
+    #[test]
+    fn shelf_should_boost_high_frequencies() {
+        let low = gain(ShelfMode::High, 6.0, 50.0);
+        let high = gain(ShelfMode::High, 6.0, 15_000.0);
+
+        assert!((low - 1.0).abs() < 0.02);
+        assert!((high - 1.995).abs() < 0.05);
+        assert!(high / low > 1.9);
+    }
+
+    #[test]
+    fn shelf_should_cut_low_frequencies() {
+        let low = gain(ShelfMode::Low, -6.0, 50.0);
+        let high = gain(ShelfMode::Low, -6.0, 15_000.0);
+
+        assert!((low - 0.501).abs() < 0.02);
+        assert!((high - 1.0).abs() < 0.02);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Analog style shift register, every rising clock edge shifts the stored values one stage
+/// down and loads the input into the first stage.
+///
+/// Each stage has its own tap, the first tap holds the input sampled on the last clock, the
+/// second tap the one sampled a clock before that and so on.
+pub struct ShiftRegister<const STAGES: usize> {
+    input: Signal,
+    clock: Signal,
+    taps: [PatchPoint; STAGES],
+    values: [f32; STAGES],
+    previous_clock: f32,
+}
+
+impl<const STAGES: usize> ShiftRegister<STAGES> {
+    pub fn new(taps: [PatchPoint; STAGES]) -> Self {
+        ShiftRegister {
+            input: Signal::None,
+            clock: Signal::None,
+            taps,
+            values: [0.0; STAGES],
+            previous_clock: 0.0,
+        }
+    }
+
+    /// Output of the stage at `index`, starting at 0 for the most recently loaded value.
+    pub fn tap(&self, index: usize) -> Signal {
+        self.taps[index].signal()
+    }
+
+    pub fn outputs(&self) -> [Signal; STAGES] {
+        let mut outputs = [Signal::None; STAGES];
+
+        for (output, tap) in outputs.iter_mut().zip(self.taps.iter()) {
+            *output = tap.signal();
+        }
+
+        outputs
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    pub fn set_clock(&mut self, signal: Signal) -> &mut Self {
+        self.clock = signal;
+        self
+    }
+}
+
+impl<const SAMPLE_RATE: usize, const STAGES: usize> Module<SAMPLE_RATE> for ShiftRegister<STAGES> {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input) && patchbay.check(self.clock)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let clock = patchbay.get(self.clock);
+
+        if clock >= 0.5 && self.previous_clock < 0.5 && STAGES > 0 {
+            self.values.copy_within(0..STAGES - 1, 1);
+            self.values[0] = patchbay.get(self.input);
+        }
+
+        self.previous_clock = clock;
+
+        for (tap, value) in self.taps.iter_mut().zip(self.values.iter()) {
+            patchbay.set(tap, *value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn shift_register_should_delay_values_by_a_clock_per_stage() {
+        let mut patchbay: Patchbay<6> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut clock = patchbay.point().unwrap();
+        let mut register: ShiftRegister<4> =
+            ShiftRegister::new(core::array::from_fn(|_| patchbay.point().unwrap()));
+
+        register.set_input(input.signal()).set_clock(clock.signal());
+
+        let values = [0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7];
+
+        for (clocks, value) in values.iter().enumerate() {
+            // Changing input between clocks is ignored
+            for i in 0..4 {
+                patchbay.set(&mut input, if i == 0 { *value } else { -1.0 });
+                patchbay.set(&mut clock, if i < 2 { 1.0 } else { 0.0 });
+                Module::<SAMPLE_RATE>::process(&mut register, &mut patchbay);
+            }
+
+            for stage in 0..4 {
+                let expected = if stage <= clocks {
+                    values[clocks - stage]
+                } else {
+                    0.0
+                };
+
+                assert_eq!(patchbay.get(register.tap(stage)), expected);
+            }
+        }
+    }
+}
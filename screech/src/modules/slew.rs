@@ -0,0 +1,118 @@
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Slew limiter for portamento and smoothing stepped CV
+///
+/// The output moves toward the input by at most the rise or fall rate, in units per second.
+pub struct Slew {
+    input: Signal,
+    output: PatchPoint,
+    rise: f32,
+    fall: f32,
+    value: f32,
+}
+
+impl Slew {
+    pub fn new(output: PatchPoint) -> Self {
+        Slew {
+            input: Signal::None,
+            output,
+            rise: f32::INFINITY,
+            fall: f32::INFINITY,
+            value: 0.0,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    /// Set the maximum rate the output rises at in units per second, defaults to no limit.
+    pub fn set_rise(&mut self, rate: f32) -> &mut Self {
+        self.rise = rate.max(0.0);
+        self
+    }
+
+    pub fn get_rise(&self) -> f32 {
+        self.rise
+    }
+
+    /// Set the maximum rate the output falls at in units per second, defaults to no limit.
+    pub fn set_fall(&mut self, rate: f32) -> &mut Self {
+        self.fall = rate.max(0.0);
+        self
+    }
+
+    pub fn get_fall(&self) -> f32 {
+        self.fall
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Slew {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let rise = self.rise / SAMPLE_RATE as f32;
+        let fall = self.fall / SAMPLE_RATE as f32;
+        let difference = patchbay.get(self.input) - self.value;
+
+        self.value += difference.clamp(-fall, rise);
+
+        patchbay.set(&mut self.output, self.value);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.value = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn slew_should_ramp_a_step_at_the_rise_and_fall_rates() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut slew = Slew::new(patchbay.point().unwrap());
+
+        // Rise from 0.0 to 1.0 in 480 samples and fall back in 960
+        slew.set_input(input.signal())
+            .set_rise(100.0)
+            .set_fall(50.0);
+
+        patchbay.set(&mut input, 1.0);
+
+        for i in 1..=500 {
+            Module::<SAMPLE_RATE>::process(&mut slew, &mut patchbay);
+            let output = patchbay.get(slew.output());
+
+            if i < 480 {
+                assert!((output - i as f32 / 480.0).abs() < 1e-4);
+            } else if i > 480 {
+                assert_eq!(output, 1.0);
+            }
+        }
+
+        patchbay.set(&mut input, 0.0);
+
+        for i in 1..=1000 {
+            Module::<SAMPLE_RATE>::process(&mut slew, &mut patchbay);
+            let output = patchbay.get(slew.output());
+
+            if i < 960 {
+                assert!((output - (1.0 - i as f32 / 960.0)).abs() < 1e-4);
+            } else if i > 960 {
+                assert_eq!(output, 0.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Oscillator looping a single cycle waveform.
+///
+/// The `N` samples of the cycle are read back at the set frequency, values in between samples
+/// are linearly interpolated, wrapping from the last sample back to the first.
+pub struct StreamOsc<const N: usize> {
+    output: PatchPoint,
+    cycle: [f32; N],
+    frequency: f32,
+    phase: f32,
+}
+
+impl<const N: usize> StreamOsc<N> {
+    /// Creates a new oscillator with a silent cycle.
+    pub fn new(output: PatchPoint) -> Self {
+        StreamOsc {
+            output,
+            cycle: [0.0; N],
+            frequency: 440.0,
+            phase: 0.0,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    /// Set the single cycle waveform to loop.
+    pub fn set_cycle(&mut self, cycle: [f32; N]) -> &mut Self {
+        self.cycle = cycle;
+        self
+    }
+
+    pub fn get_cycle(&self) -> &[f32; N] {
+        &self.cycle
+    }
+
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.frequency = frequency;
+        self
+    }
+
+    pub fn get_frequency(&self) -> f32 {
+        self.frequency
+    }
+}
+
+impl<const SAMPLE_RATE: usize, const N: usize> Module<SAMPLE_RATE> for StreamOsc<N> {
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        if N == 0 {
+            patchbay.set(&mut self.output, 0.0);
+            return;
+        }
+
+        let position = self.phase * N as f32;
+        let index = (position as usize).min(N - 1);
+        let fraction = position - index as f32;
+
+        let current = self.cycle[index];
+        let next = self.cycle[(index + 1) % N];
+
+        patchbay.set(&mut self.output, current + (next - current) * fraction);
+
+        // Advance and wrap the phase between 0.0 and 1.0
+        self.phase += self.frequency / SAMPLE_RATE as f32;
+
+        if self.phase >= 1.0 {
+            self.phase -= 1.0;
+        } else if self.phase < 0.0 {
+            self.phase += 1.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math;
+    use core::f32::consts::TAU;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    // 128 samples per cycle, so the phase increment is exact
+    const FREQUENCY: f32 = 375.0;
+
+    fn play(cycle: [f32; 64]) -> [f32; 512] {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut osc: StreamOsc<64> = StreamOsc::new(patchbay.point().unwrap());
+        let mut output = [0.0; 512];
+
+        osc.set_cycle(cycle).set_frequency(FREQUENCY);
+
+        for sample in output.iter_mut() {
+            Module::<SAMPLE_RATE>::process(&mut osc, &mut patchbay);
+            *sample = patchbay.get(osc.output());
+        }
+
+        output
+    }
+
+    #[test]
+    fn stream_osc_should_play_a_sine_cycle() {
+        let output = play(core::array::from_fn(|i| math::sin(TAU * i as f32 / 64.0)));
+
+        for (i, sample) in output.iter().enumerate() {
+            let expected = math::sin(TAU * FREQUENCY * i as f32 / SAMPLE_RATE as f32);
+            assert!((sample - expected).abs() < 2e-3);
+        }
+    }
+
+    #[test]
+    fn stream_osc_should_play_a_saw_cycle() {
+        let output = play(core::array::from_fn(|i| i as f32 / 32.0 - 1.0));
+
+        for (i, sample) in output.iter().enumerate() {
+            let position = i % 128;
+
+            // Interpolating back from the last to the first sample of the cycle
+            if position >= 126 {
+                continue;
+            }
+
+            assert!((sample - (position as f32 / 64.0 - 1.0)).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Waveshaper mapping its input through a transfer function table.
+///
+/// The input range of -1.0 to 1.0 is spread evenly across the table, values between entries are
+/// linearly interpolated. Inputs outside of that range are clamped to the first or last entry.
+pub struct TableShaper<const SIZE: usize> {
+    input: Signal,
+    output: PatchPoint,
+    table: [f32; SIZE],
+}
+
+impl<const SIZE: usize> TableShaper<SIZE> {
+    /// Creates a new shaper with an identity (straight line) table.
+    pub fn new(output: PatchPoint) -> Self {
+        let table = core::array::from_fn(|i| {
+            if SIZE > 1 {
+                (i as f32 / (SIZE - 1) as f32) * 2.0 - 1.0
+            } else {
+                0.0
+            }
+        });
+
+        TableShaper {
+            input: Signal::None,
+            output,
+            table,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    pub fn set_table(&mut self, table: [f32; SIZE]) -> &mut Self {
+        self.table = table;
+        self
+    }
+
+    pub fn get_table(&self) -> &[f32; SIZE] {
+        &self.table
+    }
+
+    /// Look up the shaped value for a single sample.
+    pub fn shape(&self, input: f32) -> f32 {
+        if SIZE < 2 {
+            return self.table.first().copied().unwrap_or(0.0);
+        }
+
+        let position = (input.clamp(-1.0, 1.0) + 1.0) * 0.5 * (SIZE - 1) as f32;
+        let index = position as usize;
+
+        if index >= SIZE - 1 {
+            return self.table[SIZE - 1];
+        }
+
+        let fraction = position - index as f32;
+
+        self.table[index] + (self.table[index + 1] - self.table[index]) * fraction
+    }
+}
+
+impl<const SAMPLE_RATE: usize, const SIZE: usize> Module<SAMPLE_RATE> for TableShaper<SIZE> {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let output = self.shape(patchbay.get(self.input));
+        patchbay.set(&mut self.output, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn table_shaper_should_pass_through_an_identity_table() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut shaper: TableShaper<17> = TableShaper::new(patchbay.point().unwrap());
+
+        shaper.set_input(input.signal());
+
+        for i in 0..=20 {
+            let value = i as f32 / 10.0 - 1.0;
+            patchbay.set(&mut input, value);
+            Module::<SAMPLE_RATE>::process(&mut shaper, &mut patchbay);
+            assert!((patchbay.get(shaper.output()) - value).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn table_shaper_should_interpolate_a_clipping_table() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut input = patchbay.point().unwrap();
+        let mut shaper: TableShaper<5> = TableShaper::new(patchbay.point().unwrap());
+
+        shaper
+            .set_input(input.signal())
+            .set_table([-0.5, -0.5, 0.0, 0.5, 0.5]);
+
+        let steps = [
+            (-1.0, -0.5),
+            (-0.75, -0.5),
+            (-0.25, -0.25),
+            (0.0, 0.0),
+            (0.25, 0.25),
+            (0.8, 0.5),
+            (2.0, 0.5),
+        ];
+
+        for (value, expected) in steps {
+            patchbay.set(&mut input, value);
+            Module::<SAMPLE_RATE>::process(&mut shaper, &mut patchbay);
+            assert!((patchbay.get(shaper.output()) - expected).abs() < 1e-6);
+        }
+    }
+}
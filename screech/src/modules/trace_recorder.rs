@@ -0,0 +1,177 @@
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Records a trace of a modulation source and replays it, e.g. to freeze a cycle of an LFO or
+/// envelope for reuse.
+///
+/// A rising edge of the record trigger starts recording up to `SIZE` samples of the input, the
+/// next rising edge stops it. While recording the input is passed through. Every rising edge of
+/// the play trigger replays the recorded trace from the start, holding the last value once the
+/// end is reached.
+pub struct TraceRecorder<const SIZE: usize> {
+    input: Signal,
+    record: Signal,
+    play: Signal,
+    output: PatchPoint,
+    buffer: [f32; SIZE],
+    length: usize,
+    position: usize,
+    recording: bool,
+    previous_record: f32,
+    previous_play: f32,
+    value: f32,
+}
+
+impl<const SIZE: usize> TraceRecorder<SIZE> {
+    pub fn new(output: PatchPoint) -> Self {
+        TraceRecorder {
+            input: Signal::None,
+            record: Signal::None,
+            play: Signal::None,
+            output,
+            buffer: [0.0; SIZE],
+            length: 0,
+            position: 0,
+            recording: false,
+            previous_record: 0.0,
+            previous_play: 0.0,
+            value: 0.0,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_input(&mut self, signal: Signal) -> &mut Self {
+        self.input = signal;
+        self
+    }
+
+    pub fn set_record(&mut self, signal: Signal) -> &mut Self {
+        self.record = signal;
+        self
+    }
+
+    pub fn set_play(&mut self, signal: Signal) -> &mut Self {
+        self.play = signal;
+        self
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// The recorded samples so far.
+    pub fn trace(&self) -> &[f32] {
+        &self.buffer[..self.length]
+    }
+}
+
+impl<const SAMPLE_RATE: usize, const SIZE: usize> Module<SAMPLE_RATE> for TraceRecorder<SIZE> {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.input) && patchbay.check(self.record) && patchbay.check(self.play)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let record = patchbay.get(self.record);
+        let play = patchbay.get(self.play);
+
+        if record >= 0.5 && self.previous_record < 0.5 {
+            self.recording = !self.recording;
+
+            if self.recording {
+                // Hold the last recorded value until the trace is played
+                self.length = 0;
+                self.position = SIZE;
+            }
+        }
+
+        if play >= 0.5 && self.previous_play < 0.5 {
+            self.position = 0;
+        }
+
+        self.previous_record = record;
+        self.previous_play = play;
+
+        if self.recording && self.length < SIZE {
+            self.value = patchbay.get(self.input);
+            self.buffer[self.length] = self.value;
+            self.length += 1;
+        } else {
+            self.recording = false;
+
+            if self.position < self.length {
+                self.value = self.buffer[self.position];
+                self.position += 1;
+            }
+        }
+
+        patchbay.set(&mut self.output, self.value);
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.length = 0;
+        self.position = 0;
+        self.recording = false;
+        self.previous_record = 0.0;
+        self.previous_play = 0.0;
+        self.value = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::Oscillator;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn trace_recorder_should_replay_a_recorded_cycle() {
+        let mut patchbay: Patchbay<4> = Patchbay::new();
+        let mut lfo = Oscillator::new(patchbay.point().unwrap());
+        let mut record = patchbay.point().unwrap();
+        let mut play = patchbay.point().unwrap();
+        let mut recorder: TraceRecorder<1024> = TraceRecorder::new(patchbay.point().unwrap());
+
+        // A cycle of 480 samples
+        lfo.output_sine().set_frequency(100.0);
+        recorder
+            .set_input(lfo.output())
+            .set_record(record.signal())
+            .set_play(play.signal());
+
+        let mut cycle = [0.0; 480];
+        let mut step = |record_value, play_value, patchbay: &mut Patchbay<4>| {
+            patchbay.set(&mut record, record_value);
+            patchbay.set(&mut play, play_value);
+            Module::<SAMPLE_RATE>::process(&mut lfo, patchbay);
+            Module::<SAMPLE_RATE>::process(&mut recorder, patchbay);
+
+            (patchbay.get(lfo.output()), patchbay.get(recorder.output()))
+        };
+
+        for (i, sample) in cycle.iter_mut().enumerate() {
+            let (lfo, output) = step(if i == 0 { 1.0 } else { 0.0 }, 0.0, &mut patchbay);
+
+            // The input is passed through while recording
+            assert_eq!(output, lfo);
+            *sample = lfo;
+        }
+
+        // Stop recording and let the LFO move on
+        for i in 0..100 {
+            let (_, output) = step(if i == 0 { 1.0 } else { 0.0 }, 0.0, &mut patchbay);
+            assert_eq!(output, cycle[479]);
+        }
+
+        for i in 0..960 {
+            let (_, output) = step(0.0, if i == 0 { 1.0 } else { 0.0 }, &mut patchbay);
+
+            // Replays the cycle and holds the last value
+            assert_eq!(output, cycle[i.min(479)]);
+        }
+
+        assert_eq!(recorder.trace(), &cycle[..]);
+    }
+}
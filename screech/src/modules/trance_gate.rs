@@ -0,0 +1,228 @@
+use crate::math::floor;
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Stereo rhythmic gate driven by an internal clock.
+///
+/// Every step of the pattern either lets the input through or mutes it. With ping-pong enabled
+/// the open steps alternate between the left and right channel. `STEPS` sets the maximum length
+/// of the pattern.
+pub struct TranceGate<const STEPS: usize> {
+    left: Signal,
+    right: Signal,
+    output_left: PatchPoint,
+    output_right: PatchPoint,
+    pattern: [bool; STEPS],
+    length: usize,
+    rate: f32,
+    ping_pong: bool,
+    phase: f32,
+    step: usize,
+}
+
+impl<const STEPS: usize> TranceGate<STEPS> {
+    /// Creates a new gate with every step of the pattern open.
+    pub fn new(output_left: PatchPoint, output_right: PatchPoint) -> Self {
+        TranceGate {
+            left: Signal::None,
+            right: Signal::None,
+            output_left,
+            output_right,
+            pattern: [true; STEPS],
+            length: STEPS,
+            rate: 8.0,
+            ping_pong: false,
+            phase: 0.0,
+            step: 0,
+        }
+    }
+
+    pub fn output_left(&self) -> Signal {
+        self.output_left.signal()
+    }
+
+    pub fn output_right(&self) -> Signal {
+        self.output_right.signal()
+    }
+
+    pub fn set_left(&mut self, signal: Signal) -> &mut Self {
+        self.left = signal;
+        self
+    }
+
+    pub fn set_right(&mut self, signal: Signal) -> &mut Self {
+        self.right = signal;
+        self
+    }
+
+    /// Set the pattern, steps beyond `STEPS` are ignored.
+    pub fn set_pattern(&mut self, pattern: &[bool]) -> &mut Self {
+        self.length = pattern.len().min(STEPS);
+        self.pattern[..self.length].copy_from_slice(&pattern[..self.length]);
+        self.step %= self.length.max(1);
+        self
+    }
+
+    pub fn get_pattern(&self) -> &[bool] {
+        &self.pattern[..self.length]
+    }
+
+    /// Set the rate in steps per second, negative rates are clamped to 0.0 and non-finite
+    /// rates to the largest finite rate.
+    pub fn set_rate(&mut self, rate: f32) -> &mut Self {
+        self.rate = if rate.is_nan() {
+            0.0
+        } else {
+            rate.clamp(0.0, f32::MAX)
+        };
+        self
+    }
+
+    pub fn get_rate(&self) -> f32 {
+        self.rate
+    }
+
+    /// Alternate the open steps between the left and right channel.
+    pub fn set_ping_pong(&mut self, ping_pong: bool) -> &mut Self {
+        self.ping_pong = ping_pong;
+        self
+    }
+}
+
+impl<const SAMPLE_RATE: usize, const STEPS: usize> Module<SAMPLE_RATE> for TranceGate<STEPS> {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.left) && patchbay.check(self.right)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let open = self.length > 0 && self.pattern[self.step];
+
+        let (left_open, right_open) = if self.ping_pong {
+            let odd = self.step & 1 == 1;
+            (open && !odd, open && odd)
+        } else {
+            (open, open)
+        };
+
+        let left = if left_open {
+            patchbay.get(self.left)
+        } else {
+            0.0
+        };
+        let right = if right_open {
+            patchbay.get(self.right)
+        } else {
+            0.0
+        };
+
+        patchbay.set(&mut self.output_left, left);
+        patchbay.set(&mut self.output_right, right);
+
+        self.phase += self.rate / SAMPLE_RATE as f32;
+
+        // Rates above the sample rate skip steps
+        let steps = floor(self.phase);
+        let length = self.length.max(1);
+
+        self.phase -= steps;
+        self.step = (self.step + steps as usize % length) % length;
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.phase = 0.0;
+        self.step = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    // One step every 1024 samples, keeps the phase increment exact
+    const STEP: usize = 1024;
+
+    fn run(gate: &mut TranceGate<8>, patchbay: &mut Patchbay<2>, steps: usize) -> [(f32, f32); 8] {
+        let mut result = [(0.0, 0.0); 8];
+
+        gate.set_left(Signal::Fixed(1.0))
+            .set_right(Signal::Fixed(1.0))
+            .set_rate(SAMPLE_RATE as f32 / STEP as f32);
+
+        for step in result.iter_mut().take(steps) {
+            for i in 0..STEP {
+                Module::<SAMPLE_RATE>::process(gate, patchbay);
+
+                let value = (
+                    patchbay.get(gate.output_left()),
+                    patchbay.get(gate.output_right()),
+                );
+
+                if i == 0 {
+                    *step = value;
+                } else {
+                    assert_eq!(value, *step);
+                }
+            }
+        }
+
+        result
+    }
+
+    #[test]
+    fn trance_gate_should_follow_the_pattern() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut gate: TranceGate<8> =
+            TranceGate::new(patchbay.point().unwrap(), patchbay.point().unwrap());
+
+        gate.set_pattern(&[true, false, true, true]);
+
+        let result = run(&mut gate, &mut patchbay, 8);
+
+        for (i, &expected) in [1.0, 0.0, 1.0, 1.0, 1.0, 0.0, 1.0, 1.0].iter().enumerate() {
+            assert_eq!(result[i], (expected, expected));
+        }
+    }
+
+    #[test]
+    fn trance_gate_should_skip_steps_at_rates_above_the_sample_rate() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut gate: TranceGate<8> =
+            TranceGate::new(patchbay.point().unwrap(), patchbay.point().unwrap());
+
+        gate.set_left(Signal::Fixed(1.0))
+            .set_pattern(&[true, false, false])
+            .set_rate(SAMPLE_RATE as f32 * 2.0);
+
+        for expected in [1.0, 0.0, 0.0, 1.0] {
+            Module::<SAMPLE_RATE>::process(&mut gate, &mut patchbay);
+            assert_eq!(patchbay.get(gate.output_left()), expected);
+        }
+
+        // Doesn't get stuck advancing the steps
+        gate.set_rate(f32::INFINITY);
+
+        for _ in 0..4 {
+            Module::<SAMPLE_RATE>::process(&mut gate, &mut patchbay);
+        }
+
+        assert_eq!(gate.get_rate(), f32::MAX);
+    }
+
+    #[test]
+    fn trance_gate_should_alternate_channels_in_ping_pong_mode() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut gate: TranceGate<8> =
+            TranceGate::new(patchbay.point().unwrap(), patchbay.point().unwrap());
+
+        gate.set_pattern(&[true, true, false, true])
+            .set_ping_pong(true);
+
+        let result = run(&mut gate, &mut patchbay, 4);
+
+        assert_eq!(result[0], (1.0, 0.0));
+        assert_eq!(result[1], (0.0, 1.0));
+        assert_eq!(result[2], (0.0, 0.0));
+        assert_eq!(result[3], (0.0, 1.0));
+    }
+}
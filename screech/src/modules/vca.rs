@@ -1,10 +1,19 @@
+use crate::math;
 use crate::{Module, PatchPoint, Patchbay, Signal};
 
 /// VCA module that takes two inputs (signal and modulator) and has a single output.
+///
+/// With plucking enabled, see [`Vca::set_pluck`], the modulator is used as a trigger instead
+/// and every rising edge starts a short exponential decay.
 pub struct Vca {
     modulator: Signal,
     input: Signal,
     output: PatchPoint,
+    pluck: f32,
+    pluck_gain: f32,
+    pluck_coefficient: f32,
+    previous_modulator: f32,
+    update: bool,
 }
 
 impl Vca {
@@ -13,6 +22,11 @@ impl Vca {
             modulator: Signal::None,
             input: Signal::None,
             output,
+            pluck: 0.0,
+            pluck_gain: 0.0,
+            pluck_coefficient: 0.0,
+            previous_modulator: 0.0,
+            update: false,
         }
     }
 
@@ -29,6 +43,19 @@ impl Vca {
         self.modulator = signal;
         self
     }
+
+    /// Treat the modulator as a trigger, every rising edge jumps the gain to 1.0 after which
+    /// it decays exponentially to -60dB over `decay_ms` milliseconds. A value of `0.0`
+    /// (default) disables plucking.
+    pub fn set_pluck(&mut self, decay_ms: f32) -> &mut Self {
+        self.pluck = decay_ms;
+        self.update = true;
+        self
+    }
+
+    pub fn get_pluck(&self) -> f32 {
+        self.pluck
+    }
 }
 
 impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Vca {
@@ -37,10 +64,87 @@ impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Vca {
     }
 
     fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let modulator = patchbay.get(self.modulator);
+
+        let gain = if self.pluck > 0.0 {
+            if self.update {
+                // Reach a thousandth (-60dB) after `pluck` milliseconds
+                self.pluck_coefficient =
+                    math::exp(math::ln(0.001) / (self.pluck / 1000.0 * SAMPLE_RATE as f32));
+                self.update = false;
+            }
+
+            if modulator >= 0.5 && self.previous_modulator < 0.5 {
+                self.pluck_gain = 1.0;
+            } else {
+                self.pluck_gain *= self.pluck_coefficient;
+            }
+
+            self.pluck_gain
+        } else {
+            modulator
+        };
+
+        self.previous_modulator = modulator;
+
         // Take the input signal and multiply it by the modulator input.
-        patchbay.set(
-            &mut self.output,
-            patchbay.get(self.input) * patchbay.get(self.modulator),
-        );
+        patchbay.set(&mut self.output, patchbay.get(self.input) * gain);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn vca_should_multiply_the_input_by_the_modulator() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut vca = Vca::new(patchbay.point().unwrap());
+
+        vca.set_input(Signal::Fixed(0.5))
+            .set_modulator(Signal::Fixed(0.25));
+        Module::<SAMPLE_RATE>::process(&mut vca, &mut patchbay);
+
+        assert_eq!(patchbay.get(vca.output()), 0.125);
+    }
+
+    #[test]
+    fn vca_should_pluck_on_a_trigger() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut trigger = patchbay.point().unwrap();
+        let mut vca = Vca::new(patchbay.point().unwrap());
+
+        // 100ms of decay
+        vca.set_input(Signal::Fixed(0.5))
+            .set_modulator(trigger.signal())
+            .set_pluck(100.0);
+
+        patchbay.set(&mut trigger, 0.0);
+        Module::<SAMPLE_RATE>::process(&mut vca, &mut patchbay);
+        assert_eq!(patchbay.get(vca.output()), 0.0);
+
+        // Jumping up, no matter how long the trigger stays high
+        patchbay.set(&mut trigger, 1.0);
+        Module::<SAMPLE_RATE>::process(&mut vca, &mut patchbay);
+        assert_eq!(patchbay.get(vca.output()), 0.5);
+
+        let mut previous = 0.5;
+        let mut ratio = None;
+
+        for _ in 0..SAMPLE_RATE / 10 {
+            Module::<SAMPLE_RATE>::process(&mut vca, &mut patchbay);
+            let output = patchbay.get(vca.output());
+
+            // Falling by the same ratio every sample
+            let step = output / previous;
+            assert!((step - *ratio.get_or_insert(step)).abs() < 1e-6);
+            assert!(step < 1.0);
+
+            previous = output;
+        }
+
+        assert!((previous / 0.5 - 0.001).abs() < 1e-4);
     }
 }
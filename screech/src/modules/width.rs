@@ -0,0 +1,143 @@
+use crate::{Module, PatchPoint, Patchbay, Signal};
+
+/// Stereo width meter.
+///
+/// Measures the ratio of side energy to mid energy over a window, `0.0` means mono content and
+/// larger values mean wider content. Fully out of phase content has no mid energy at all and
+/// results in very large values. The output is updated at the end of every window.
+pub struct Width {
+    left: Signal,
+    right: Signal,
+    output: PatchPoint,
+    window: f32,
+    mid_energy: f32,
+    side_energy: f32,
+    count: usize,
+    width: f32,
+}
+
+impl Width {
+    pub fn new(output: PatchPoint) -> Self {
+        Width {
+            left: Signal::None,
+            right: Signal::None,
+            output,
+            window: 0.05,
+            mid_energy: 0.0,
+            side_energy: 0.0,
+            count: 0,
+            width: 0.0,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_left(&mut self, signal: Signal) -> &mut Self {
+        self.left = signal;
+        self
+    }
+
+    pub fn set_right(&mut self, signal: Signal) -> &mut Self {
+        self.right = signal;
+        self
+    }
+
+    /// Set the measuring window in seconds.
+    pub fn set_window(&mut self, seconds: f32) -> &mut Self {
+        self.window = seconds;
+        self
+    }
+
+    pub fn get_window(&self) -> f32 {
+        self.window
+    }
+
+    /// Width measured over the last complete window.
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+}
+
+impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Width {
+    fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+        patchbay.check(self.left) && patchbay.check(self.right)
+    }
+
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        let left = patchbay.get(self.left);
+        let right = patchbay.get(self.right);
+
+        let mid = (left + right) * 0.5;
+        let side = (left - right) * 0.5;
+
+        self.mid_energy += mid * mid;
+        self.side_energy += side * side;
+        self.count += 1;
+
+        let window = ((self.window * SAMPLE_RATE as f32) as usize).max(1);
+
+        if self.count >= window {
+            self.width = if self.side_energy > 0.0 {
+                self.side_energy / self.mid_energy.max(f32::EPSILON)
+            } else {
+                0.0
+            };
+
+            self.mid_energy = 0.0;
+            self.side_energy = 0.0;
+            self.count = 0;
+        }
+
+        patchbay.set(&mut self.output, self.width);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math;
+    use core::f32::consts::PI;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    fn measure(left_gain: f32, right_gain: f32) -> f32 {
+        let mut patchbay: Patchbay<3> = Patchbay::new();
+        let mut left = patchbay.point().unwrap();
+        let mut right = patchbay.point().unwrap();
+        let mut width = Width::new(patchbay.point().unwrap());
+
+        width
+            .set_left(left.signal())
+            .set_right(right.signal())
+            .set_window(0.01);
+
+        for i in 0..480 {
+            let sample = math::sin(2.0 * PI * 440.0 * i as f32 / SAMPLE_RATE as f32);
+            patchbay.set(&mut left, sample * left_gain);
+            patchbay.set(&mut right, sample * right_gain);
+            Module::<SAMPLE_RATE>::process(&mut width, &mut patchbay);
+        }
+
+        assert_eq!(patchbay.get(width.output()), width.width());
+
+        width.width()
+    }
+
+    #[test]
+    fn width_should_be_zero_for_mono_content() {
+        assert_eq!(measure(0.5, 0.5), 0.0);
+    }
+
+    #[test]
+    fn width_should_increase_for_wider_content() {
+        let mono = measure(0.5, 0.5);
+        let panned = measure(0.8, 0.2);
+        let opposite = measure(0.5, -0.5);
+
+        assert!(panned > mono);
+        assert!((panned - 0.36).abs() < 1e-3);
+        assert!(opposite > 1000.0);
+    }
+}
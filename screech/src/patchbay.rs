@@ -1,5 +1,37 @@
 use crate::Signal;
 
+/// Abstraction to refer to a pair of left and right sample values, see [`Signal`].
+#[derive(Copy, Clone)]
+pub enum StereoSignal {
+    /// Refers to two adjacent samples set by a [`StereoPatchPoint`], with the generation of the
+    /// points, see [`Signal::PatchPoint`]
+    PatchPoint(usize, u32),
+    /// Fixed left and right sample values.
+    Fixed(f32, f32),
+    /// No signal, reads as silence on both channels.
+    None,
+}
+
+impl StereoSignal {
+    /// The left channel as a mono [`Signal`].
+    pub fn left(&self) -> Signal {
+        match *self {
+            StereoSignal::PatchPoint(id, generation) => Signal::PatchPoint(id, generation),
+            StereoSignal::Fixed(left, _) => Signal::Fixed(left),
+            StereoSignal::None => Signal::None,
+        }
+    }
+
+    /// The right channel as a mono [`Signal`].
+    pub fn right(&self) -> Signal {
+        match *self {
+            StereoSignal::PatchPoint(id, generation) => Signal::PatchPoint(id + 1, generation),
+            StereoSignal::Fixed(_, right) => Signal::Fixed(right),
+            StereoSignal::None => Signal::None,
+        }
+    }
+}
+
 /// Virtual patchbay holding sample values.
 ///
 /// ```
@@ -17,6 +49,14 @@ use crate::Signal;
 pub struct Patchbay<const PATCHPOINTS: usize> {
     buffer: [f32; PATCHPOINTS],
     marks: [bool; PATCHPOINTS],
+    allocated: [bool; PATCHPOINTS],
+    generations: [u32; PATCHPOINTS],
+}
+
+impl<const PATCHPOINTS: usize> Default for Patchbay<PATCHPOINTS> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<const PATCHPOINTS: usize> Patchbay<PATCHPOINTS> {
@@ -24,25 +64,101 @@ impl<const PATCHPOINTS: usize> Patchbay<PATCHPOINTS> {
         Patchbay {
             buffer: [0.0; PATCHPOINTS],
             marks: [false; PATCHPOINTS],
+            allocated: [false; PATCHPOINTS],
+            generations: [0; PATCHPOINTS],
         }
     }
 
     /// Get a free [`PatchPoint`], returns `None` if all available points are taken.
     pub fn point(&mut self) -> Option<PatchPoint> {
-        for i in 0..PATCHPOINTS {
-            if !self.marks[i] {
-                self.marks[i] = true;
-                return Some(PatchPoint::new(i));
+        let id = self.allocated.iter().position(|allocated| !allocated)?;
+        self.allocated[id] = true;
+
+        Some(PatchPoint::new(id, self.generations[id]))
+    }
+
+    /// Return a point to the patchbay so it can be handed out again, the sample value is reset
+    /// to silence. Signals taken from the point before releasing it read as silence from then
+    /// on, even after the point has been handed out again.
+    ///
+    /// Modules still reading from a released point read silence and no longer wait on it, so
+    /// the order a [`crate::Processor`] sorted its modules in can be out of date. Call
+    /// [`crate::Processor::clear_cache`] after repatching to have the order recalculated.
+    ///
+    /// ```
+    /// use screech::Patchbay;
+    ///
+    /// let mut patchbay: Patchbay<1> = Patchbay::new();
+    ///
+    /// let point = patchbay.point().unwrap();
+    /// assert_eq!(patchbay.free_points(), 0);
+    ///
+    /// patchbay.release(point);
+    /// assert_eq!(patchbay.free_points(), 1);
+    /// ```
+    pub fn release(&mut self, point: PatchPoint) {
+        if self.generations[point.id] == point.generation {
+            self.buffer[point.id] = 0.0;
+            self.marks[point.id] = false;
+            self.allocated[point.id] = false;
+            self.generations[point.id] = self.generations[point.id].wrapping_add(1);
+        }
+    }
+
+    /// Return both points of a [`StereoPatchPoint`] to the patchbay, see [`Patchbay::release`].
+    pub fn release_stereo(&mut self, point: StereoPatchPoint) {
+        self.release(PatchPoint::new(point.id, point.generation));
+        self.release(PatchPoint::new(point.id + 1, point.generation));
+    }
+
+    /// Amount of points that can still be handed out.
+    pub fn free_points(&self) -> usize {
+        self.allocated
+            .iter()
+            .filter(|allocated| !**allocated)
+            .count()
+    }
+
+    /// Get a free [`StereoPatchPoint`] taking up two adjacent points, returns `None` if there
+    /// are no two adjacent points available.
+    ///
+    /// ```
+    /// use screech::{Patchbay, StereoSignal};
+    ///
+    /// let mut patchbay: Patchbay<4> = Patchbay::new();
+    ///
+    /// let mut point = patchbay.stereo_point().unwrap();
+    /// patchbay.set_stereo(&mut point, (0.2, 0.8));
+    ///
+    /// assert_eq!(patchbay.get_stereo(point.signal()), (0.2, 0.8));
+    /// assert_eq!(patchbay.get(point.signal().right()), 0.8);
+    /// assert_eq!(patchbay.get_stereo(StereoSignal::Fixed(0.1, 0.3)), (0.1, 0.3));
+    /// ```
+    pub fn stereo_point(&mut self) -> Option<StereoPatchPoint> {
+        for i in 1..PATCHPOINTS {
+            if !self.allocated[i - 1] && !self.allocated[i] {
+                self.allocated[i - 1] = true;
+                self.allocated[i] = true;
+
+                // Share a generation newer than any signal of either point
+                let generation = self.generations[i - 1].max(self.generations[i]);
+                self.generations[i - 1] = generation;
+                self.generations[i] = generation;
+
+                return Some(StereoPatchPoint::new(i - 1, generation));
             }
         }
 
         None
     }
 
-    /// Get the sample value of a signal.
+    /// Get the sample value of a signal, signals of released points read as silence.
     pub fn get(&self, signal: Signal) -> f32 {
         match signal {
-            Signal::PatchPoint(id) => self.buffer[id],
+            Signal::PatchPoint(id, generation) if self.generations[id] == generation => {
+                self.buffer[id]
+            }
+            Signal::PatchPoint(_, _) => 0.0,
             Signal::Fixed(s) => s,
             Signal::None => 0.0,
         }
@@ -50,36 +166,299 @@ impl<const PATCHPOINTS: usize> Patchbay<PATCHPOINTS> {
 
     /// Set the sample value of a patchpoint using the exclusive ownership.
     pub fn set(&mut self, point: &mut PatchPoint, sample: f32) {
-        self.buffer[point.id] = sample;
-        self.marks[point.id] = true;
+        if self.generations[point.id] == point.generation {
+            self.buffer[point.id] = sample;
+            self.marks[point.id] = true;
+        }
+    }
+
+    /// Get the left and right sample values of a stereo signal.
+    pub fn get_stereo(&self, signal: StereoSignal) -> (f32, f32) {
+        (self.get(signal.left()), self.get(signal.right()))
     }
 
-    /// Check if a patchpoint sample value is up to date.
+    /// Set the left and right sample values of a stereo patchpoint.
+    pub fn set_stereo(&mut self, point: &mut StereoPatchPoint, (left, right): (f32, f32)) {
+        if self.generations[point.id] == point.generation
+            && self.generations[point.id + 1] == point.generation
+        {
+            self.buffer[point.id] = left;
+            self.buffer[point.id + 1] = right;
+            self.marks[point.id] = true;
+            self.marks[point.id + 1] = true;
+        }
+    }
+
+    /// Check if both sample values of a stereo signal are up to date.
+    pub fn check_stereo(&self, signal: StereoSignal) -> bool {
+        self.check(signal.left()) && self.check(signal.right())
+    }
+
+    /// Check if a patchpoint sample value is up to date, signals of released points are
+    /// always up to date as they read as silence.
     pub fn check(&self, signal: Signal) -> bool {
         match signal {
-            Signal::PatchPoint(id) => self.marks[id],
+            Signal::PatchPoint(id, generation) if self.generations[id] == generation => {
+                self.marks[id]
+            }
+            Signal::PatchPoint(_, _) => true,
             Signal::Fixed(_) => true,
             Signal::None => true,
         }
     }
 
+    /// Iterate over the id and sample value of every marked point, e.g. for metering.
+    ///
+    /// ```
+    /// use screech::Patchbay;
+    ///
+    /// let mut patchbay: Patchbay<4> = Patchbay::new();
+    /// let mut point = patchbay.point().unwrap();
+    ///
+    /// patchbay.set(&mut point, -0.5);
+    ///
+    /// assert!(patchbay.active_points().eq([(0, -0.5)]));
+    /// assert_eq!(patchbay.max_abs(), 0.5);
+    /// ```
+    pub fn active_points(&self) -> impl Iterator<Item = (usize, f32)> + '_ {
+        self.marks
+            .iter()
+            .zip(self.buffer.iter())
+            .enumerate()
+            .filter(|(_, (mark, _))| **mark)
+            .map(|(id, (_, value))| (id, *value))
+    }
+
+    /// Highest absolute sample value of all marked points.
+    pub fn max_abs(&self) -> f32 {
+        self.active_points()
+            .fold(0.0, |max, (_, value)| max.max(value.abs()))
+    }
+
+    // Id of the point a signal refers to, `None` for other signals and released points
+    pub(crate) fn point_id(&self, signal: Signal) -> Option<usize> {
+        match signal {
+            Signal::PatchPoint(id, generation) if self.generations[id] == generation => Some(id),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn samples_mut(&mut self) -> &mut [f32; PATCHPOINTS] {
+        &mut self.buffer
+    }
+
     pub fn clear_marks(&mut self) {
         for m in self.marks.iter_mut() {
             *m = false;
         }
     }
+
+    /// Zero all sample values and marks, e.g. between rendering independent takes.
+    ///
+    /// Points handed out before stay allocated and can be set and read as usual, use
+    /// [`Patchbay::release`] to hand them back.
+    pub fn reset(&mut self) {
+        self.buffer = [0.0; PATCHPOINTS];
+        self.clear_marks();
+    }
 }
 
 pub struct PatchPoint {
     id: usize,
+    generation: u32,
 }
 
 impl PatchPoint {
-    pub(crate) fn new(id: usize) -> Self {
-        PatchPoint { id }
+    pub(crate) fn new(id: usize, generation: u32) -> Self {
+        PatchPoint { id, generation }
     }
 
     pub fn signal(&self) -> Signal {
-        Signal::PatchPoint(self.id)
+        Signal::PatchPoint(self.id, self.generation)
+    }
+}
+
+/// Pair of adjacent points in a [`Patchbay`] holding a left and right sample value.
+pub struct StereoPatchPoint {
+    id: usize,
+    generation: u32,
+}
+
+impl StereoPatchPoint {
+    pub(crate) fn new(id: usize, generation: u32) -> Self {
+        StereoPatchPoint { id, generation }
+    }
+
+    pub fn signal(&self) -> StereoSignal {
+        StereoSignal::PatchPoint(self.id, self.generation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stereo_point_should_take_two_adjacent_points() {
+        let mut patchbay: Patchbay<5> = Patchbay::new();
+
+        let _a = patchbay.point().unwrap();
+        let mut stereo = patchbay.stereo_point().unwrap();
+        let mut b = patchbay.point().unwrap();
+
+        patchbay.set_stereo(&mut stereo, (0.25, -0.5));
+        patchbay.set(&mut b, 1.0);
+
+        assert_eq!(patchbay.get_stereo(stereo.signal()), (0.25, -0.5));
+        assert_eq!(patchbay.get(stereo.signal().left()), 0.25);
+        assert_eq!(patchbay.get(stereo.signal().right()), -0.5);
+        assert_eq!(patchbay.get(b.signal()), 1.0);
+
+        // Only a single point left
+        assert!(patchbay.stereo_point().is_none());
+        assert!(patchbay.point().is_some());
+    }
+
+    #[test]
+    fn stereo_point_should_skip_gaps_of_a_single_point() {
+        let mut patchbay: Patchbay<4> = Patchbay::new();
+
+        let a = patchbay.point().unwrap();
+        let _b = patchbay.point().unwrap();
+
+        // Free the first point again, leaving a gap of a single point
+        patchbay.release(a);
+
+        let stereo = patchbay.stereo_point().unwrap();
+
+        assert!(matches!(stereo.signal().left(), Signal::PatchPoint(2, _)));
+        assert!(matches!(stereo.signal().right(), Signal::PatchPoint(3, _)));
+    }
+
+    #[test]
+    fn patchbay_should_reuse_released_points() {
+        let mut patchbay: Patchbay<8> = Patchbay::new();
+        let mut points: [Option<PatchPoint>; 8] = Default::default();
+
+        for point in points.iter_mut() {
+            let mut p = patchbay.point().unwrap();
+            patchbay.set(&mut p, 1.0);
+            *point = Some(p);
+        }
+
+        assert!(patchbay.point().is_none());
+        assert_eq!(patchbay.free_points(), 0);
+
+        for point in points.iter_mut().step_by(2) {
+            patchbay.release(point.take().unwrap());
+        }
+
+        assert_eq!(patchbay.free_points(), 4);
+
+        for point in points.iter_mut().step_by(2) {
+            let p = patchbay.point().unwrap();
+
+            // Released points start out silent
+            assert_eq!(patchbay.get(p.signal()), 0.0);
+            *point = Some(p);
+        }
+
+        assert!(patchbay.point().is_none());
+        assert_eq!(patchbay.free_points(), 0);
+    }
+
+    #[test]
+    fn patchbay_should_silence_signals_of_released_points() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+
+        let mut point = patchbay.point().unwrap();
+        let stale = point.signal();
+
+        patchbay.set(&mut point, 0.5);
+        assert_eq!(patchbay.get(stale), 0.5);
+
+        patchbay.release(point);
+
+        // The same slot is handed out again
+        let mut point = patchbay.point().unwrap();
+        patchbay.set(&mut point, 1.0);
+
+        assert_eq!(patchbay.get(point.signal()), 1.0);
+        assert_eq!(patchbay.get(stale), 0.0);
+        assert!(patchbay.check(stale));
+    }
+
+    #[test]
+    fn patchbay_should_reuse_released_stereo_points() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+
+        let mut point = patchbay.stereo_point().unwrap();
+        let stale = point.signal();
+
+        patchbay.set_stereo(&mut point, (0.2, 0.8));
+        assert_eq!(patchbay.free_points(), 0);
+
+        patchbay.release_stereo(point);
+        assert_eq!(patchbay.free_points(), 2);
+
+        let mut point = patchbay.stereo_point().unwrap();
+
+        // Released points start out silent
+        assert_eq!(patchbay.get_stereo(point.signal()), (0.0, 0.0));
+
+        patchbay.set_stereo(&mut point, (0.4, 0.6));
+
+        assert_eq!(patchbay.get_stereo(point.signal()), (0.4, 0.6));
+        assert_eq!(patchbay.get_stereo(stale), (0.0, 0.0));
+    }
+
+    #[test]
+    fn patchbay_should_iterate_over_active_points() {
+        let mut patchbay: Patchbay<6> = Patchbay::new();
+        let mut points: [PatchPoint; 4] = core::array::from_fn(|_| patchbay.point().unwrap());
+
+        patchbay.clear_marks();
+        patchbay.set(&mut points[1], 0.25);
+        patchbay.set(&mut points[3], -0.75);
+
+        let mut active = patchbay.active_points();
+
+        assert_eq!(active.next(), Some((1, 0.25)));
+        assert_eq!(active.next(), Some((3, -0.75)));
+        assert_eq!(active.next(), None);
+
+        assert_eq!(patchbay.max_abs(), 0.75);
+    }
+
+    #[test]
+    fn patchbay_should_reset_samples_and_marks() {
+        let mut patchbay: Patchbay<3> = Patchbay::new();
+        let mut a = patchbay.point().unwrap();
+        let mut b = patchbay.point().unwrap();
+
+        patchbay.set(&mut a, 0.5);
+        patchbay.set(&mut b, -0.5);
+        patchbay.reset();
+
+        assert_eq!(patchbay.get(a.signal()), 0.0);
+        assert_eq!(patchbay.get(b.signal()), 0.0);
+        assert!(!patchbay.check(a.signal()));
+        assert_eq!(patchbay.active_points().count(), 0);
+
+        patchbay.set(&mut a, 0.25);
+        assert_eq!(patchbay.get(a.signal()), 0.25);
+        assert!(patchbay.check(a.signal()));
+
+        // Points stay allocated, a new point doesn't share a slot with them
+        assert_eq!(patchbay.free_points(), 1);
+
+        let mut c = patchbay.point().unwrap();
+        patchbay.set(&mut b, 0.75);
+        patchbay.set(&mut c, -0.25);
+
+        assert_eq!(patchbay.get(a.signal()), 0.25);
+        assert_eq!(patchbay.get(b.signal()), 0.75);
+        assert_eq!(patchbay.get(c.signal()), -0.25);
+        assert!(patchbay.point().is_none());
     }
 }
@@ -0,0 +1,102 @@
+use crate::math;
+use crate::{Patchbay, StereoSignal};
+
+/// Correlation meter for a [`StereoSignal`], telling how well it survives being summed to mono.
+///
+/// Captured samples accumulate until [`PhaseMeter::reset`] is called, so reset it for every
+/// stretch of audio that should be judged on its own.
+///
+/// ```
+/// use screech::{Patchbay, PhaseMeter, StereoSignal};
+///
+/// let patchbay: Patchbay<1> = Patchbay::new();
+/// let mut meter = PhaseMeter::new(StereoSignal::Fixed(0.5, -0.5));
+///
+/// meter.capture(&patchbay);
+/// assert_eq!(meter.mono_compatibility(), -1.0);
+/// ```
+pub struct PhaseMeter {
+    input: StereoSignal,
+    left: f32,
+    right: f32,
+    product: f32,
+}
+
+impl PhaseMeter {
+    pub fn new(input: StereoSignal) -> Self {
+        PhaseMeter {
+            input,
+            left: 0.0,
+            right: 0.0,
+            product: 0.0,
+        }
+    }
+
+    pub fn set_input(&mut self, input: StereoSignal) -> &mut Self {
+        self.input = input;
+        self
+    }
+
+    /// Add the current left and right sample values of the input to the measurement.
+    pub fn capture<const P: usize>(&mut self, patchbay: &Patchbay<P>) {
+        let (left, right) = patchbay.get_stereo(self.input);
+
+        self.left += left * left;
+        self.right += right * right;
+        self.product += left * right;
+    }
+
+    /// Correlation between the left and right channel, from 1.0 for identical channels through
+    /// 0.0 for unrelated channels to -1.0 for channels cancelling each other out when summed.
+    /// Scores below zero warn of out of phase content, silence scores 1.0.
+    pub fn mono_compatibility(&self) -> f32 {
+        let energy = math::sqrt(self.left * self.right);
+
+        if energy > 0.0 {
+            (self.product / energy).clamp(-1.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.left = 0.0;
+        self.right = 0.0;
+        self.product = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::f32::consts::TAU;
+
+    // Mono compatibility of a sine with the right channel shifted by `shift` cycles
+    fn score(shift: f32) -> f32 {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut point = patchbay.stereo_point().unwrap();
+        let mut meter = PhaseMeter::new(point.signal());
+
+        for i in 0..4800 {
+            let phase = i as f32 / 480.0;
+            let left = math::sin(TAU * phase);
+            let right = math::sin(TAU * (phase + shift));
+
+            patchbay.set_stereo(&mut point, (left, right));
+            meter.capture(&patchbay);
+        }
+
+        meter.mono_compatibility()
+    }
+
+    #[test]
+    fn phase_meter_should_score_in_phase_content_higher() {
+        let in_phase = score(0.0);
+        let quarter = score(0.25);
+        let anti_phase = score(0.5);
+
+        assert!(in_phase > 0.99);
+        assert!(quarter.abs() < 0.01);
+        assert!(anti_phase < -0.99);
+    }
+}
@@ -0,0 +1,177 @@
+//! Pitch conversion helpers.
+
+use crate::math;
+
+/// Default tuning reference, the frequency of A4 (MIDI note 69) in Hz.
+pub const A4: f32 = 440.0;
+
+/// Convert a MIDI note number to a frequency in Hz, tuned to A4 = 440Hz.
+///
+/// Fractional note numbers are allowed for microtonal pitches.
+///
+/// ```
+/// use screech::pitch::midi_to_freq;
+///
+/// assert_eq!(midi_to_freq(69.0), 440.0);
+/// assert_eq!(midi_to_freq(57.0), 220.0);
+/// ```
+pub fn midi_to_freq(note: f32) -> f32 {
+    midi_to_freq_ref(note, A4)
+}
+
+/// Convert a MIDI note number to a frequency in Hz, tuned to a given reference for A4.
+///
+/// Notes further than 64 octaves away from A4 are clamped to 64 octaves.
+///
+/// ```
+/// use screech::pitch::midi_to_freq_ref;
+///
+/// assert_eq!(midi_to_freq_ref(69.0, 432.0), 432.0);
+/// ```
+pub fn midi_to_freq_ref(note: f32, a4: f32) -> f32 {
+    let octaves = (note - 69.0) / 12.0;
+    let whole = math::floor(octaves);
+    let fraction = octaves - whole;
+    let whole = whole.clamp(-64.0, 64.0);
+
+    // Whole octaves are exact doublings, only the remainder needs a power function
+    let mut frequency = if fraction > 0.0 {
+        a4 * math::pow(2.0, fraction)
+    } else {
+        a4
+    };
+
+    for _ in 0..(whole.abs() as usize) {
+        if whole < 0.0 {
+            frequency *= 0.5;
+        } else {
+            frequency *= 2.0;
+        }
+    }
+
+    frequency
+}
+
+/// Set of semitones within an octave making up a musical scale, used to quantize pitches.
+///
+/// ```
+/// use screech::pitch::Scale;
+///
+/// assert_eq!(Scale::MAJOR.quantize(6.4), 7.0);
+/// assert_eq!(Scale::MAJOR.quantize(-1.2), -1.0);
+/// assert_eq!(Scale::new(&[0, 7]).quantize(3.0), 0.0);
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Scale {
+    semitones: u16,
+}
+
+impl Scale {
+    pub const CHROMATIC: Scale = Scale { semitones: 0xfff };
+    pub const MAJOR: Scale = Scale {
+        semitones: 0b1010_1011_0101,
+    };
+    pub const MINOR: Scale = Scale {
+        semitones: 0b0101_1010_1101,
+    };
+    pub const MAJOR_PENTATONIC: Scale = Scale {
+        semitones: 0b0010_1001_0101,
+    };
+    pub const MINOR_PENTATONIC: Scale = Scale {
+        semitones: 0b0100_1010_1001,
+    };
+
+    /// Create a scale from semitones above the root, wrapped to a single octave.
+    pub fn new(semitones: &[u8]) -> Self {
+        Scale {
+            semitones: semitones.iter().fold(0, |mask, s| mask | 1 << (s % 12)),
+        }
+    }
+
+    pub fn contains(&self, semitone: u8) -> bool {
+        self.semitones & 1 << (semitone % 12) != 0
+    }
+
+    /// Snap a pitch in semitones above the root to the nearest semitone in the scale, on a tie
+    /// the lower one is picked. An empty scale leaves the pitch untouched, as do non-finite
+    /// pitches and pitches of 2^23 semitones or more away from the root.
+    pub fn quantize(&self, semitones: f32) -> f32 {
+        if self.semitones == 0 || semitones.is_nan() || semitones.abs() >= 8_388_608.0 {
+            return semitones;
+        }
+
+        let base = math::floor(semitones) as i32;
+
+        // Every semitone in the scale is within an octave of the pitch
+        (base - 12..=base + 13)
+            .filter(|note| self.contains(note.rem_euclid(12) as u8))
+            .map(|note| note as f32)
+            .fold(f32::MAX, |nearest, note| {
+                if (note - semitones).abs() < (nearest - semitones).abs() {
+                    note
+                } else {
+                    nearest
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn midi_to_freq_should_map_a4_to_the_reference() {
+        assert_eq!(midi_to_freq(69.0), 440.0);
+        assert_eq!(midi_to_freq_ref(69.0, 432.0), 432.0);
+        assert_eq!(midi_to_freq_ref(69.0, 415.0), 415.0);
+    }
+
+    #[test]
+    fn midi_to_freq_should_keep_intervals_relative_to_the_reference() {
+        for reference in [440.0, 432.0, 415.0] {
+            assert_eq!(midi_to_freq_ref(81.0, reference), reference * 2.0);
+            assert_eq!(midi_to_freq_ref(45.0, reference), reference / 4.0);
+
+            // Equal tempered fifth and semitone
+            let fifth = midi_to_freq_ref(76.0, reference) / reference;
+            let semitone = midi_to_freq_ref(70.0, reference) / reference;
+
+            assert!((fifth - 1.498_307).abs() < 1e-4);
+            assert!((semitone - 1.059_463).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn midi_to_freq_should_clamp_far_away_notes() {
+        let highest = 440.0 * 2.0f32.powi(64);
+
+        assert_eq!(midi_to_freq(69.0 + 12.0 * 64.0), highest);
+        assert_eq!(midi_to_freq(69.0 + 12.0 * 100.0), highest);
+        assert_eq!(midi_to_freq(69.0 - 12.0 * 100.0), 440.0 / 2.0f32.powi(64));
+        assert_eq!(midi_to_freq(f32::INFINITY), highest);
+    }
+
+    #[test]
+    fn scale_should_quantize_to_the_nearest_semitone_in_the_scale() {
+        let expected = [
+            0.0, 0.0, 2.0, 2.0, 4.0, 5.0, 5.0, 7.0, 7.0, 9.0, 9.0, 11.0, 12.0,
+        ];
+
+        for (semitone, expected) in expected.iter().enumerate() {
+            assert_eq!(Scale::MAJOR.quantize(semitone as f32), *expected);
+        }
+
+        assert_eq!(Scale::MINOR_PENTATONIC.quantize(-13.4), -14.0);
+        assert_eq!(Scale::CHROMATIC.quantize(3.6), 4.0);
+        assert_eq!(Scale::new(&[]).quantize(3.6), 3.6);
+    }
+
+    #[test]
+    fn scale_should_leave_out_of_range_pitches_untouched() {
+        assert_eq!(Scale::MAJOR.quantize(f32::INFINITY), f32::INFINITY);
+        assert_eq!(Scale::MAJOR.quantize(f32::NEG_INFINITY), f32::NEG_INFINITY);
+        assert_eq!(Scale::MAJOR.quantize(3e9), 3e9);
+        assert!(Scale::MAJOR.quantize(f32::NAN).is_nan());
+    }
+}
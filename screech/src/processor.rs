@@ -1,4 +1,4 @@
-use crate::{Module, Patchbay};
+use crate::{BlockPatchbay, Event, EventQueue, Module, Patchbay, Signal};
 
 #[derive(PartialEq)]
 enum Mode {
@@ -6,6 +6,14 @@ enum Mode {
     B,
 }
 
+/// Errors reported by a [`Processor`].
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ProcessorError {
+    /// Modules depend on each other in a cycle that has not been flagged as feedback, see
+    /// [`Processor::set_feedback`].
+    CyclicDependencies,
+}
+
 /// Processor for [Module]s.
 ///
 /// Keeps track of the dependencies between modules and runs the [`Module::process`] fn
@@ -16,6 +24,10 @@ pub struct Processor<const SAMPLE_RATE: usize, const MODULES: usize, M: Module<S
     pub modules: [Option<M>; MODULES],
     pub module_ids: [Option<usize>; MODULES],
     pub order_set: bool,
+    order: [usize; MODULES],
+    order_length: usize,
+    choke_groups: [Option<usize>; MODULES],
+    feedback: [bool; MODULES],
     mode: Mode,
 }
 
@@ -30,6 +42,10 @@ impl<const SAMPLE_RATE: usize, const MODULES: usize, M: Module<SAMPLE_RATE>>
             modules,
             module_ids,
             order_set: false,
+            order: [0; MODULES],
+            order_length: 0,
+            choke_groups: [None; MODULES],
+            feedback: [false; MODULES],
             mode: Mode::A,
         }
     }
@@ -40,6 +56,10 @@ impl<const SAMPLE_RATE: usize, const MODULES: usize, M: Module<SAMPLE_RATE>>
             modules: core::array::from_fn(|_| None),
             module_ids: [None; MODULES],
             order_set: false,
+            order: [0; MODULES],
+            order_length: 0,
+            choke_groups: [None; MODULES],
+            feedback: [false; MODULES],
             mode: Mode::A,
         }
     }
@@ -70,13 +90,11 @@ impl<const SAMPLE_RATE: usize, const MODULES: usize, M: Module<SAMPLE_RATE>>
     /// assert_eq!(processor.take_modules(), [None, None, None, None]);
     /// ```
     pub fn take_modules(&mut self) -> [Option<M>; MODULES] {
-        let mut modules = core::array::from_fn(|_| None);
-
-        for i in 0..MODULES {
-            modules[i] = self.modules[i].take();
-        }
+        let modules = core::array::from_fn(|i| self.modules[i].take());
 
         self.module_ids = [None; MODULES];
+        self.choke_groups = [None; MODULES];
+        self.feedback = [false; MODULES];
 
         modules
     }
@@ -107,6 +125,49 @@ impl<const SAMPLE_RATE: usize, const MODULES: usize, M: Module<SAMPLE_RATE>>
         self.module_ids[index].and_then(move |i| self.modules[i].as_mut())
     }
 
+    /// Iterate over all modules along with their index.
+    ///
+    /// ```
+    /// use screech::Processor;
+    /// use screech::modules::Dummy;
+    ///
+    /// let processor: Processor<48_000, 3, Dummy> = Processor::new([None, Some(Dummy), None]);
+    ///
+    /// assert!(processor.iter_modules().eq([(1, &Dummy)]));
+    /// ```
+    pub fn iter_modules(&self) -> impl Iterator<Item = (usize, &M)> + '_ {
+        let indices = self.slot_indices();
+
+        self.modules
+            .iter()
+            .zip(indices)
+            .filter_map(|(module, index)| Some((index?, module.as_ref()?)))
+    }
+
+    /// Iterate over mutable references to all modules along with their index, e.g. to update a
+    /// parameter on every module of a kind.
+    pub fn iter_modules_mut(&mut self) -> impl Iterator<Item = (usize, &mut M)> + '_ {
+        let indices = self.slot_indices();
+
+        self.modules
+            .iter_mut()
+            .zip(indices)
+            .filter_map(|(module, index)| Some((index?, module.as_mut()?)))
+    }
+
+    // Index of the module held in every slot of `modules`
+    fn slot_indices(&self) -> [Option<usize>; MODULES] {
+        let mut indices = [None; MODULES];
+
+        for (index, id) in self.module_ids.iter().enumerate() {
+            if let Some(id) = id {
+                indices[*id] = Some(index);
+            }
+        }
+
+        indices
+    }
+
     /// Insert a module
     ///
     /// ```
@@ -205,6 +266,31 @@ impl<const SAMPLE_RATE: usize, const MODULES: usize, M: Module<SAMPLE_RATE>>
             }
         }
     }
+
+    /// Remove the module at a given index, freeing the index for [`Processor::insert_module`].
+    ///
+    /// ```
+    /// use screech::Processor;
+    /// use screech::modules::Dummy;
+    ///
+    /// let mut processor: Processor<48_000, 2, Dummy> = Processor::new([Some(Dummy), None]);
+    ///
+    /// assert_eq!(processor.remove_module(0), Some(Dummy));
+    /// assert_eq!(processor.remove_module(0), None);
+    /// ```
+    pub fn remove_module(&mut self, index: usize) -> Option<M> {
+        let module = self.module_ids[index].and_then(|i| self.modules[i].take());
+
+        self.module_ids[index] = None;
+        self.choke_groups[index] = None;
+        self.feedback[index] = false;
+
+        // Bust the cache
+        self.order_set = false;
+
+        module
+    }
+
     /// Callback to process modules, usually called from a loop to process the entire buffer.
     ///
     /// ```
@@ -229,96 +315,394 @@ impl<const SAMPLE_RATE: usize, const MODULES: usize, M: Module<SAMPLE_RATE>>
         if !self.order_set {
             self.order_and_process_modules(patchbay);
         } else {
-            for i in 0..MODULES {
-                match self.modules[i].as_mut() {
-                    Some(m) => m.process(patchbay),
-                    None => break,
+            self.process_ordered_modules(patchbay);
+        }
+    }
+
+    /// Process a block of `samples` samples in one call, amortizing the per call overhead of
+    /// [`Processor::process_modules`].
+    ///
+    /// The `frame` callback is called after every processed sample with the index of the sample
+    /// within the block, use it to read outputs and set inputs for the next sample.
+    ///
+    /// ```
+    /// use screech::{Patchbay, Processor};
+    /// use screech::modules::Oscillator;
+    ///
+    /// const BUFFER_SIZE: usize = 256;
+    /// const SAMPLE_RATE: usize = 48_000;
+    ///
+    /// let mut patchbay: Patchbay<8> = Patchbay::new();
+    /// let osc = Oscillator::new(patchbay.point().unwrap());
+    /// let output = osc.output();
+    /// let mut processor: Processor<SAMPLE_RATE, 1, Oscillator> = Processor::new([Some(osc)]);
+    /// let mut buffer = [0.0; BUFFER_SIZE];
+    ///
+    /// processor.process_samples(&mut patchbay, BUFFER_SIZE, |i, patchbay| {
+    ///     buffer[i] = patchbay.get(output);
+    /// });
+    /// ```
+    pub fn process_samples<const P: usize, F: FnMut(usize, &mut Patchbay<P>)>(
+        &mut self,
+        patchbay: &mut Patchbay<P>,
+        samples: usize,
+        mut frame: F,
+    ) {
+        let mut start = 0;
+
+        // Sort once up front, the rest of the block runs in the cached order
+        if samples > 0 && !self.order_set {
+            self.order_and_process_modules(patchbay);
+            frame(0, patchbay);
+            start = 1;
+        }
+
+        for i in start..samples {
+            self.process_ordered_modules(patchbay);
+            frame(i, patchbay);
+        }
+    }
+
+    /// Process `N` samples and fill a buffer with the sample values of an output signal.
+    ///
+    /// ```
+    /// use screech::{Patchbay, Processor};
+    /// use screech::modules::Oscillator;
+    ///
+    /// let mut patchbay: Patchbay<8> = Patchbay::new();
+    /// let osc = Oscillator::new(patchbay.point().unwrap());
+    /// let output = osc.output();
+    /// let mut processor: Processor<48_000, 1, Oscillator> = Processor::new([Some(osc)]);
+    /// let mut buffer = [0.0; 256];
+    ///
+    /// processor.process_buffer(&mut patchbay, output, &mut buffer);
+    /// ```
+    pub fn process_buffer<const P: usize, const N: usize>(
+        &mut self,
+        patchbay: &mut Patchbay<P>,
+        output_signal: Signal,
+        out: &mut [f32; N],
+    ) {
+        self.process_samples(patchbay, N, |i, patchbay| {
+            out[i] = patchbay.get(output_signal);
+        });
+    }
+
+    /// Process a block of `N` samples module by module, letting modules work on the whole block
+    /// at once using [`Module::process_block`].
+    ///
+    /// Every module processes the whole block before the next one runs, so modules in a
+    /// feedback loop read the previous block instead of the previous sample. Until a processing
+    /// order has been determined the block is processed sample by sample to sort the modules.
+    pub fn process_block<const P: usize, const N: usize>(
+        &mut self,
+        patchbay: &mut BlockPatchbay<P, N>,
+    ) {
+        if !self.order_set {
+            for frame in 0..N {
+                patchbay.load_frame(frame);
+                self.process_modules(patchbay.patchbay_mut());
+                patchbay.store_frame(frame);
+            }
+
+            return;
+        }
+
+        for &id in self.order[..self.order_length].iter() {
+            if let Some(m) = self.modules[id].as_mut() {
+                m.process_block(patchbay);
+            }
+        }
+    }
+
+    fn process_ordered_modules<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        for &id in self.order[..self.order_length].iter() {
+            if let Some(m) = self.modules[id].as_mut() {
+                m.process(patchbay);
+            }
+        }
+    }
+
+    /// Reset every module to its initial state using [`Module::reset`].
+    pub fn reset_modules<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        for module in self.modules.iter_mut().flatten() {
+            module.reset(patchbay);
+        }
+    }
+
+    /// Advance the running ramps of every module by one sample using
+    /// [`Module::advance_ramps`], call this before processing the modules for the sample.
+    ///
+    /// Ramps are started using [`Module::ramp`], this way automation for every module in a
+    /// processor is driven from one place.
+    ///
+    /// ```
+    /// use screech::{Module, Patchbay, Processor};
+    /// use screech::modules::Oscillator;
+    ///
+    /// let mut patchbay: Patchbay<1> = Patchbay::new();
+    /// let osc = Oscillator::new(patchbay.point().unwrap());
+    /// let mut processor: Processor<48_000, 1, Oscillator> = Processor::new([Some(osc)]);
+    ///
+    /// if let Some(osc) = processor.get_module_mut(0) {
+    ///     Module::<48_000>::ramp(osc, Oscillator::FREQUENCY, 880.0, 256);
+    /// }
+    ///
+    /// for _ in 0..256 {
+    ///     processor.advance_ramps();
+    ///     processor.process_modules(&mut patchbay);
+    /// }
+    ///
+    /// assert_eq!(processor.get_module(0).unwrap().get_frequency(), 880.0);
+    /// ```
+    pub fn advance_ramps(&mut self) {
+        for module in self.modules.iter_mut().flatten() {
+            module.advance_ramps();
+        }
+    }
+
+    /// Assign the module at a given index to a choke group, or remove it from its group with
+    /// `None`.
+    ///
+    /// When a module in a group receives an [`Event::NoteOn`] through
+    /// [`Processor::process_modules_with_events`] the other modules in the same group receive
+    /// an [`Event::Choke`], e.g. to have a closed hi-hat cut off an open one.
+    pub fn set_choke_group(&mut self, index: usize, group: Option<usize>) {
+        self.choke_groups[index] = group;
+    }
+
+    pub fn get_choke_group(&self, index: usize) -> Option<usize> {
+        self.choke_groups[index]
+    }
+
+    /// Deliver the events due at the current sample of the queue using
+    /// [`Module::handle_event`], then process the modules and advance the queue.
+    ///
+    /// ```
+    /// use screech::{Event, EventQueue, Patchbay, Processor};
+    /// use screech::modules::Dummy;
+    ///
+    /// let mut patchbay: Patchbay<8> = Patchbay::new();
+    /// let mut processor: Processor<48_000, 1, Dummy> = Processor::new([Some(Dummy)]);
+    /// let mut events: EventQueue<16> = EventQueue::new();
+    ///
+    /// events.push(100, 0, Event::NoteOn { note: 60, velocity: 1.0 });
+    ///
+    /// for _ in 0..256 {
+    ///   processor.process_modules_with_events(&mut patchbay, &mut events);
+    /// }
+    ///
+    /// assert!(events.is_empty());
+    /// ```
+    pub fn process_modules_with_events<const P: usize, const E: usize>(
+        &mut self,
+        patchbay: &mut Patchbay<P>,
+        events: &mut EventQueue<E>,
+    ) {
+        while let Some((index, event)) = events.pop() {
+            if let Some(module) = self.get_module_mut(index) {
+                module.handle_event(event);
+            }
+
+            if let (Event::NoteOn { .. }, Some(group)) = (event, self.choke_groups[index]) {
+                for other in 0..MODULES {
+                    if other != index && self.choke_groups[other] == Some(group) {
+                        if let Some(module) = self.get_module_mut(other) {
+                            module.handle_event(Event::Choke);
+                        }
+                    }
                 }
             }
         }
+
+        self.process_modules(patchbay);
+        events.advance();
     }
 
-    fn order_and_process_modules<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+    /// Sort and process the modules, returns `false` if modules had to be processed before
+    /// they were ready without being flagged as feedback.
+    fn order_and_process_modules<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) -> bool {
         patchbay.clear_marks();
 
-        let mut new_index = 0;
-        let mut new_order: [Option<usize>; MODULES] = [None; MODULES];
+        let mut order_length = 0;
         let mut processed = [false; MODULES];
+        let feedback = self.feedback;
+        let mut feedback_pass = false;
 
         loop {
             let mut updated_modules = 0;
 
-            for index in 0..MODULES {
-                match (
-                    processed[index],
-                    self.module_ids[index].and_then(|id| self.modules[id].as_mut()),
-                ) {
-                    // If it has not been processed already and contains a module
-                    (false, Some(m)) => {
-                        if m.is_ready(patchbay) {
+            let slots = processed
+                .iter_mut()
+                .zip(self.module_ids.iter())
+                .zip(feedback.iter());
+
+            for ((processed, module_id), feedback) in slots {
+                // If it has not been processed already and contains a module
+                if let (false, Some(id)) = (*processed, *module_id) {
+                    if let Some(m) = self.modules[id].as_mut() {
+                        if m.is_ready(patchbay) || (feedback_pass && *feedback) {
                             // Process the module so the outputs are set.
                             m.process(patchbay);
                             // Mark as already processed
-                            processed[index] = true;
+                            *processed = true;
                             // Put it in cache processing order
-                            new_order[index] = Some(new_index);
-                            new_index += 1;
+                            self.order[order_length] = id;
+                            order_length += 1;
                             // Tell the loop something has changed, so keep going
                             updated_modules += 1;
                         }
                     }
-                    _ => (),
                 }
             }
 
-            if updated_modules == 0 {
+            if updated_modules > 0 {
+                feedback_pass = false;
+            } else if feedback_pass {
                 break;
+            } else {
+                // Nothing is ready, break cycles at the modules flagged as feedback
+                feedback_pass = true;
             }
         }
 
+        let ordered = (0..MODULES).all(|i| processed[i] || self.module_ids[i].is_none());
+
         // Process and sort the remaining non ready modules
-        for index in 0..MODULES {
-            match (
-                processed[index],
-                self.module_ids[index].and_then(|id| self.modules[id].as_mut()),
-            ) {
-                (false, Some(m)) => {
+        for (processed, module_id) in processed.iter().zip(self.module_ids.iter()) {
+            if let (false, Some(id)) = (*processed, *module_id) {
+                if let Some(m) = self.modules[id].as_mut() {
                     // Process the module so the outputs are set.
                     m.process(patchbay);
                     // Put it in cache processing order
-                    new_order[index] = Some(new_index);
-                    new_index += 1;
+                    self.order[order_length] = id;
+                    order_length += 1;
                 }
-                _ => (),
             }
         }
 
-        let mut modules_cache: [Option<M>; MODULES] = core::array::from_fn(|_| None);
+        self.order_length = order_length;
+        self.order_set = true;
+
+        ordered
+    }
+
+    /// Indices of the modules in the order they are processed in, for debugging. Empty until
+    /// an order has been determined by processing the modules or [`Processor::set_order`].
+    pub fn processing_order(&self) -> impl Iterator<Item = usize> + '_ {
+        let indices = self.slot_indices();
+        let length = if self.order_set { self.order_length } else { 0 };
+
+        self.order[..length]
+            .iter()
+            .filter_map(move |&id| indices[id])
+    }
+
+    pub fn clear_cache(&mut self) {
+        self.order_set = false;
+    }
+
+    /// Flag the module at a given index as part of an intentional feedback loop.
+    ///
+    /// When no module is ready to be processed the flagged modules are processed first, reading
+    /// the previous sample of their inputs, to break the cycle. Cycles without a flagged module
+    /// are reported by [`Processor::try_order`].
+    pub fn set_feedback(&mut self, index: usize, feedback: bool) {
+        self.feedback[index] = feedback;
+
+        // Bust the cache
+        self.order_set = false;
+    }
+
+    pub fn get_feedback(&self, index: usize) -> bool {
+        self.feedback[index]
+    }
+
+    /// Determine the processing order and process the modules for one sample, like
+    /// [`Processor::process_modules`] does when no order is set.
+    ///
+    /// Instead of silently guessing an order for modules depending on each other in a cycle
+    /// an error is returned if none of the modules in the cycle are flagged using
+    /// [`Processor::set_feedback`]. The modules are processed and ordered either way.
+    pub fn try_order<const P: usize>(
+        &mut self,
+        patchbay: &mut Patchbay<P>,
+    ) -> Result<(), ProcessorError> {
+        if self.order_and_process_modules(patchbay) {
+            Ok(())
+        } else {
+            Err(ProcessorError::CyclicDependencies)
+        }
+    }
 
-        // Reorder the modules
-        for index in 0..MODULES {
-            if let Some(old_id) = self.module_ids[index] {
-                let new_id = new_order[index].unwrap_or(old_id);
-                modules_cache[new_id] = self.modules[old_id].take();
-                self.module_ids[index] = Some(new_id);
+    /// Set the processing order manually, skipping the dependency sort on the next
+    /// [`Processor::process_modules`] call.
+    ///
+    /// Useful for static patches where the order is already known, avoiding the performance
+    /// hit of sorting on a real time thread. The order lists module indices (as returned by
+    /// [`Processor::insert_module`]) and has to contain every module exactly once, otherwise
+    /// the order is rejected and `false` is returned.
+    ///
+    /// ```
+    /// use screech::Processor;
+    /// use screech::modules::Dummy;
+    ///
+    /// let mut processor: Processor<48_000, 3, Dummy> = Processor::new([Some(Dummy), None, Some(Dummy)]);
+    ///
+    /// assert!(processor.set_order(&[2, 0]));
+    /// assert!(!processor.set_order(&[2]));
+    /// assert!(!processor.set_order(&[2, 1]));
+    /// ```
+    pub fn set_order(&mut self, order: &[usize]) -> bool {
+        let mut seen = [false; MODULES];
+
+        for &index in order {
+            if index >= MODULES || seen[index] || self.module_ids[index].is_none() {
+                return false;
             }
+
+            seen[index] = true;
+        }
+
+        if order.len() != self.module_ids.iter().filter(|id| id.is_some()).count() {
+            return false;
         }
 
-        // Swap the modules
-        self.modules = modules_cache;
+        for (position, &index) in order.iter().enumerate() {
+            if let Some(id) = self.module_ids[index] {
+                self.order[position] = id;
+            }
+        }
 
+        self.order_length = order.len();
         self.order_set = true;
+
+        true
     }
 
-    pub fn clear_cache(&mut self) {
-        self.order_set = false;
+    /// Sum the [`Module::latency`] of every module along a path of module indices.
+    ///
+    /// Use this to compensate parallel chains that pass through latency introducing modules.
+    /// Indices without a module are skipped.
+    pub fn total_latency(&self, path: &[usize]) -> usize {
+        path.iter()
+            .filter_map(|&index| self.get_module(index))
+            .map(|m| m.latency())
+            .sum()
+    }
+
+    /// Sum the [`Module::cost_hint`] of every module, e.g. to keep a patch within the budget
+    /// of an embedded target.
+    pub fn total_cost(&self) -> u32 {
+        self.modules.iter().flatten().map(|m| m.cost_hint()).sum()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::modules::Dummy;
+    use crate::modules::{Dummy, Oscillator};
+    use crate::test_utils::assert_output_sequence;
     use crate::{PatchPoint, Patchbay, Signal};
     use screech_macro::modularize;
 
@@ -370,11 +754,111 @@ mod tests {
         }
     }
 
+    struct Delay {
+        buffer: [f32; 4],
+        position: usize,
+        input: Signal,
+        output: PatchPoint,
+    }
+
+    impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Delay {
+        fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+            patchbay.check(self.input)
+        }
+
+        fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+            patchbay.set(&mut self.output, self.buffer[self.position]);
+            self.buffer[self.position] = patchbay.get(self.input);
+            self.position = (self.position + 1) % self.buffer.len();
+        }
+
+        fn latency(&self) -> usize {
+            self.buffer.len()
+        }
+
+        fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+            self.buffer = [0.0; 4];
+            self.position = 0;
+        }
+    }
+
+    struct Listener {
+        samples: usize,
+        received: Option<(usize, Event)>,
+    }
+
+    impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Listener {
+        fn process<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+            self.samples += 1;
+        }
+
+        fn handle_event(&mut self, event: Event) {
+            self.received = Some((self.samples, event));
+        }
+    }
+
+    struct Voice {
+        level: f32,
+        output: PatchPoint,
+    }
+
+    impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Voice {
+        fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+            patchbay.set(&mut self.output, self.level);
+        }
+
+        fn handle_event(&mut self, event: Event) {
+            self.level = match event {
+                Event::NoteOn { velocity, .. } => velocity,
+                Event::NoteOff { .. } | Event::Choke => 0.0,
+            };
+        }
+    }
+
     #[modularize]
     enum Modules {
         Constant(Constant),
         Divide(Divide),
         Add(Add),
+        Delay(Delay),
+        Listener(Listener),
+        Voice(Voice),
+    }
+
+    impl Constant {
+        fn outputs(&self) -> [Signal; 2] {
+            [self.output.signal(), Signal::None]
+        }
+    }
+
+    struct Split {
+        input: Signal,
+        left: PatchPoint,
+        right: PatchPoint,
+    }
+
+    impl Split {
+        fn outputs(&self) -> [Signal; 2] {
+            [self.left.signal(), self.right.signal()]
+        }
+    }
+
+    impl<const SAMPLE_RATE: usize> Module<SAMPLE_RATE> for Split {
+        fn is_ready<const P: usize>(&self, patchbay: &Patchbay<P>) -> bool {
+            patchbay.check(self.input)
+        }
+
+        fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+            let input = patchbay.get(self.input);
+            patchbay.set(&mut self.left, input);
+            patchbay.set(&mut self.right, -input);
+        }
+    }
+
+    #[modularize(outputs = 2)]
+    enum MultiOutputModules {
+        Constant(Constant),
+        Split(Split),
     }
 
     #[test]
@@ -410,6 +894,58 @@ mod tests {
         assert_eq!(processor.take_modules(), [Some(Dummy), None, None, None]);
     }
 
+    #[test]
+    fn process_should_allow_removing_modules() {
+        let mut processor: Processor<SAMPLE_RATE, 3, Dummy> = Processor::empty();
+
+        let ids = [(); 3].map(|_| processor.insert_module(Dummy).unwrap());
+        assert_eq!(processor.insert_module(Dummy), None);
+
+        assert_eq!(processor.remove_module(ids[1]), Some(Dummy));
+        assert_eq!(processor.get_module(ids[1]), None);
+        assert_eq!(processor.remove_module(ids[1]), None);
+
+        // The freed slot is used again
+        assert_eq!(processor.insert_module(Dummy), Some(ids[1]));
+        assert_eq!(
+            processor.take_modules(),
+            [Some(Dummy), Some(Dummy), Some(Dummy)]
+        );
+    }
+
+    #[test]
+    fn process_should_allow_iterating_over_modules() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut processor: Processor<SAMPLE_RATE, 4, Modules> = Processor::empty();
+
+        for (value, index) in [(0.1, 3), (0.2, 1)] {
+            let output = patchbay.point().unwrap();
+            processor.replace_module(Modules::Constant(Constant { value, output }), index);
+        }
+
+        for (_, module) in processor.iter_modules_mut() {
+            if let Modules::Constant(c) = module {
+                c.value *= 2.0;
+            }
+        }
+
+        let values = |processor: &Processor<SAMPLE_RATE, 4, Modules>, index| match processor
+            .get_module(index)
+        {
+            Some(Modules::Constant(c)) => Some(c.value),
+            _ => None,
+        };
+
+        assert_eq!(values(&processor, 3), Some(0.2));
+        assert_eq!(values(&processor, 1), Some(0.4));
+
+        let mut indices = processor.iter_modules().map(|(index, _)| index);
+
+        assert_eq!(indices.next(), Some(3));
+        assert_eq!(indices.next(), Some(1));
+        assert_eq!(indices.next(), None);
+    }
+
     #[test]
     fn process_should_allow_getting_modules() {
         let mut processor: Processor<SAMPLE_RATE, 4, Dummy> =
@@ -439,6 +975,28 @@ mod tests {
         assert_eq!(patchbay.get(signal), 0.8);
     }
 
+    #[test]
+    fn process_should_keep_points_allocated() {
+        let mut patchbay: Patchbay<4> = Patchbay::new();
+        let output = patchbay.point().unwrap();
+        let mut unset = patchbay.point().unwrap();
+        let mut processor: Processor<SAMPLE_RATE, 1, _> =
+            Processor::new([Some(Modules::Constant(Constant { value: 0.8, output }))]);
+
+        processor.process_modules(&mut patchbay);
+
+        // Ordering clears the marks, points that haven't been set yet are still taken
+        assert_eq!(patchbay.free_points(), 2);
+
+        let mut point = patchbay.point().unwrap();
+
+        patchbay.set(&mut unset, 0.25);
+        patchbay.set(&mut point, 0.5);
+
+        assert_eq!(patchbay.get(unset.signal()), 0.25);
+        assert_eq!(patchbay.get(point.signal()), 0.5);
+    }
+
     #[test]
     fn process_should_run_modules_in_the_correct_order() {
         let mut patchbay: Patchbay<32> = Patchbay::new();
@@ -466,9 +1024,59 @@ mod tests {
             Some(Modules::Constant(constant)),
         ]);
 
+        assert_eq!(processor.processing_order().count(), 0);
+
         processor.process_modules(&mut patchbay);
 
         assert_eq!(patchbay.get(output), 0.1);
+        assert!(processor.processing_order().eq([2, 1, 0]));
+    }
+
+    #[test]
+    fn process_should_use_a_manually_set_order() {
+        let mut patchbay: Patchbay<32> = Patchbay::new();
+
+        let constant = Constant {
+            value: 0.8,
+            output: patchbay.point().unwrap(),
+        };
+        let divide1 = Divide {
+            value: 4.0,
+            input: constant.output.signal(),
+            output: patchbay.point().unwrap(),
+        };
+        let divide2 = Divide {
+            value: 2.0,
+            input: divide1.output.signal(),
+            output: patchbay.point().unwrap(),
+        };
+
+        let output = divide2.output.signal();
+
+        let mut processor: Processor<SAMPLE_RATE, 3, _> = Processor::new([
+            Some(Modules::Divide(divide2)),
+            Some(Modules::Divide(divide1)),
+            Some(Modules::Constant(constant)),
+        ]);
+
+        // Invalid orders are rejected
+        assert!(!processor.set_order(&[2, 1]));
+        assert!(!processor.set_order(&[2, 1, 1]));
+        assert!(!processor.set_order(&[2, 1, 3]));
+        assert!(!processor.order_set);
+
+        assert!(processor.set_order(&[2, 1, 0]));
+        assert!(processor.order_set);
+
+        processor.process_modules(&mut patchbay);
+        assert_eq!(patchbay.get(output), 0.1);
+
+        // A deliberately wrong order is used as is, proving no sorting happens
+        assert!(processor.set_order(&[0, 1, 2]));
+
+        let mut patchbay: Patchbay<32> = Patchbay::new();
+        processor.process_modules(&mut patchbay);
+        assert_eq!(patchbay.get(output), 0.0);
     }
 
     #[test]
@@ -499,10 +1107,449 @@ mod tests {
             Some(Modules::Divide(divide)),
         ]);
 
+        // Each sample adds half of the previous output
+        assert_output_sequence(
+            &mut processor,
+            &mut patchbay,
+            output,
+            &[0.8, 1.2, 1.4, 1.5, 1.55],
+        );
+    }
+
+    #[test]
+    fn process_should_sum_the_cost_of_modules() {
+        #[modularize]
+        enum CostModules {
+            Oscillator(Oscillator),
+            ConvReverb(crate::modules::ConvReverb<16>),
+        }
+
+        let mut patchbay: Patchbay<5> = Patchbay::new();
+        let mut processor: Processor<SAMPLE_RATE, 5, CostModules> = Processor::empty();
+
+        for _ in 0..4 {
+            let osc = Oscillator::new(patchbay.point().unwrap());
+            processor.insert_module(CostModules::Oscillator(osc));
+        }
+
+        let oscillators = processor.total_cost();
+        assert_eq!(oscillators, 4);
+
+        let reverb = crate::modules::ConvReverb::new(patchbay.point().unwrap());
+        processor.insert_module(CostModules::ConvReverb(reverb));
+
+        assert!(processor.total_cost() > oscillators);
+    }
+
+    #[test]
+    fn try_order_should_report_cyclic_dependencies() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let a_output = patchbay.point().unwrap();
+        let b_output = patchbay.point().unwrap();
+
+        let a = Divide {
+            value: 2.0,
+            input: b_output.signal(),
+            output: a_output,
+        };
+        let b = Divide {
+            value: 2.0,
+            input: a.output.signal(),
+            output: b_output,
+        };
+
+        let mut processor: Processor<SAMPLE_RATE, 2, _> =
+            Processor::new([Some(Modules::Divide(a)), Some(Modules::Divide(b))]);
+
+        assert_eq!(
+            processor.try_order(&mut patchbay),
+            Err(ProcessorError::CyclicDependencies)
+        );
+
+        processor.set_feedback(1, true);
+        assert_eq!(processor.try_order(&mut patchbay), Ok(()));
+    }
+
+    #[test]
+    fn process_should_sum_latency_along_a_path() {
+        let mut patchbay: Patchbay<3> = Patchbay::new();
+
+        let constant = Constant {
+            value: 0.8,
+            output: patchbay.point().unwrap(),
+        };
+        let delay1 = Delay {
+            buffer: [0.0; 4],
+            position: 0,
+            input: constant.output.signal(),
+            output: patchbay.point().unwrap(),
+        };
+        let delay2 = Delay {
+            buffer: [0.0; 4],
+            position: 0,
+            input: delay1.output.signal(),
+            output: patchbay.point().unwrap(),
+        };
+
+        let output = delay2.output.signal();
+
+        let mut processor: Processor<SAMPLE_RATE, 3, _> = Processor::new([
+            Some(Modules::Delay(delay2)),
+            Some(Modules::Constant(constant)),
+            Some(Modules::Delay(delay1)),
+        ]);
+
+        assert_eq!(
+            processor.get_module(0).map(Module::<SAMPLE_RATE>::latency),
+            Some(4)
+        );
+        assert_eq!(processor.total_latency(&[1]), 0);
+        assert_eq!(processor.total_latency(&[1, 2, 0]), 8);
+
+        for _ in 0..8 {
+            processor.process_modules(&mut patchbay);
+            assert_eq!(patchbay.get(output), 0.0);
+        }
+
         processor.process_modules(&mut patchbay);
         assert_eq!(patchbay.get(output), 0.8);
+    }
+
+    #[test]
+    fn process_should_deliver_events_at_the_scheduled_sample() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut events: EventQueue<4> = EventQueue::new();
+        let mut processor: Processor<SAMPLE_RATE, 2, _> = Processor::new([
+            Some(Modules::Listener(Listener {
+                samples: 0,
+                received: None,
+            })),
+            Some(Modules::Listener(Listener {
+                samples: 0,
+                received: None,
+            })),
+        ]);
+
+        let note = Event::NoteOn {
+            note: 60,
+            velocity: 1.0,
+        };
+
+        events.push(100, 1, note);
+
+        for _ in 0..256 {
+            processor.process_modules_with_events(&mut patchbay, &mut events);
+        }
+
+        let received = |m: Option<&Modules>| match m {
+            Some(Modules::Listener(l)) => l.received,
+            _ => None,
+        };
+
+        // Delivered right before the 101st call to process, after 100 processed samples
+        assert_eq!(received(processor.get_module(1)), Some((100, note)));
+        assert_eq!(received(processor.get_module(0)), None);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn process_should_choke_other_modules_in_the_group() {
+        let mut patchbay: Patchbay<3> = Patchbay::new();
+        let mut events: EventQueue<4> = EventQueue::new();
+        let voices: [Voice; 3] = core::array::from_fn(|_| Voice {
+            level: 0.0,
+            output: patchbay.point().unwrap(),
+        });
+        let outputs: [Signal; 3] = core::array::from_fn(|i| voices[i].output.signal());
+        let mut processor: Processor<SAMPLE_RATE, 3, _> =
+            Processor::new(voices.map(|v| Some(Modules::Voice(v))));
+
+        processor.set_choke_group(0, Some(1));
+        processor.set_choke_group(1, Some(1));
+
+        let note = Event::NoteOn {
+            note: 46,
+            velocity: 1.0,
+        };
+
+        events.push(0, 0, note);
+        events.push(0, 2, note);
+        processor.process_modules_with_events(&mut patchbay, &mut events);
+
+        assert_eq!(outputs.map(|o| patchbay.get(o)), [1.0, 0.0, 1.0]);
+
+        // Triggering the second module of the group silences the first one
+        events.push(0, 1, note);
+        processor.process_modules_with_events(&mut patchbay, &mut events);
+
+        assert_eq!(outputs.map(|o| patchbay.get(o)), [0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn process_should_process_a_block_of_samples() {
+        let mut patchbay: Patchbay<4> = Patchbay::new();
+
+        let constant = Constant {
+            value: 0.8,
+            output: patchbay.point().unwrap(),
+        };
+        let delay = Delay {
+            buffer: [0.0; 4],
+            position: 0,
+            input: constant.output.signal(),
+            output: patchbay.point().unwrap(),
+        };
+
+        let output = delay.output.signal();
+
+        let mut processor: Processor<SAMPLE_RATE, 2, _> = Processor::new([
+            Some(Modules::Delay(delay)),
+            Some(Modules::Constant(constant)),
+        ]);
+
+        let mut buffer = [0.0; 8];
+
+        processor.process_samples(&mut patchbay, 8, |i, patchbay| {
+            buffer[i] = patchbay.get(output);
+        });
+
+        assert!(processor.order_set);
+        assert_eq!(buffer, [0.0, 0.0, 0.0, 0.0, 0.8, 0.8, 0.8, 0.8]);
+    }
+
+    #[test]
+    fn process_should_fill_a_buffer() {
+        let new_processor = |patchbay: &mut Patchbay<1>| {
+            let mut osc = Oscillator::new(patchbay.point().unwrap());
+            osc.set_frequency(220.0);
+            let output = osc.output();
+
+            (Processor::<SAMPLE_RATE, 1, _>::new([Some(osc)]), output)
+        };
+
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let (mut processor, output) = new_processor(&mut patchbay);
+        let mut expected = [0.0; 256];
+
+        for sample in expected.iter_mut() {
+            processor.process_modules(&mut patchbay);
+            *sample = patchbay.get(output);
+        }
+
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let (mut processor, output) = new_processor(&mut patchbay);
+        let mut buffer = [0.0; 256];
+
+        processor.process_buffer(&mut patchbay, output, &mut buffer);
+
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn process_block_should_match_processing_per_sample() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+        osc.output_saw().set_frequency(220.0);
+        let output = osc.output();
+
+        let mut processor: Processor<SAMPLE_RATE, 1, _> = Processor::new([Some(osc)]);
+        let mut expected = [0.0; 256];
+
+        processor.process_buffer(&mut patchbay, output, &mut expected);
+
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+        osc.output_saw().set_frequency(220.0);
+
+        let mut processor: Processor<SAMPLE_RATE, 1, _> = Processor::new([Some(osc)]);
+        let mut block: BlockPatchbay<1, 64> = BlockPatchbay::new(patchbay);
+
+        // The first block sorts the modules sample by sample, the others run per module
+        for expected in expected.chunks(64) {
+            processor.process_block(&mut block);
+            assert_eq!(&block.get_block(output)[..], expected);
+        }
+    }
+
+    #[modularize(sample_rate = 48_000)]
+    enum FixedRateModules {
+        Constant(Constant),
+        Divide(Divide),
+    }
+
+    #[test]
+    fn modularize_should_allow_a_fixed_sample_rate() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let constant = Constant {
+            value: 0.5,
+            output: patchbay.point().unwrap(),
+        };
+        let divide = Divide {
+            value: 2.0,
+            input: constant.output.signal(),
+            output: patchbay.point().unwrap(),
+        };
+        let output = divide.output.signal();
+
+        let mut processor: Processor<48_000, 2, FixedRateModules> = Processor::new([
+            Some(FixedRateModules::Divide(divide)),
+            Some(FixedRateModules::Constant(constant)),
+        ]);
 
         processor.process_modules(&mut patchbay);
-        assert_eq!(patchbay.get(output), 1.2);
+        assert_eq!(patchbay.get(output), 0.25);
+    }
+
+    #[modularize]
+    enum NamedModules {
+        Constant(Constant),
+        Delay(Delay),
+        Dummy(Dummy),
+    }
+
+    #[test]
+    fn modularize_should_forward_reset_and_name() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let constant = Constant {
+            value: 0.5,
+            output: patchbay.point().unwrap(),
+        };
+        let delay = Delay {
+            buffer: [0.0; 4],
+            position: 0,
+            input: constant.output.signal(),
+            output: patchbay.point().unwrap(),
+        };
+        let output = delay.output.signal();
+
+        let mut processor: Processor<SAMPLE_RATE, 3, _> = Processor::new([
+            Some(NamedModules::Constant(constant)),
+            Some(NamedModules::Delay(delay)),
+            Some(NamedModules::Dummy(Dummy)),
+        ]);
+
+        // Fill the delay buffer
+        for _ in 0..5 {
+            processor.process_modules(&mut patchbay);
+        }
+
+        assert_eq!(patchbay.get(output), 0.5);
+
+        processor.reset_modules(&mut patchbay);
+
+        // The emptied buffer delays the constant again
+        for _ in 0..4 {
+            processor.process_modules(&mut patchbay);
+            assert_eq!(patchbay.get(output), 0.0);
+        }
+
+        processor.process_modules(&mut patchbay);
+        assert_eq!(patchbay.get(output), 0.5);
+
+        let names = [0, 1, 2].map(|i| processor.get_module(i).map(|m| m.name()));
+
+        assert_eq!(names, [Some("Constant"), Some("Delay"), Some("Dummy")]);
+    }
+
+    #[modularize]
+    enum RenamedModules {
+        Osc(Oscillator),
+        Delay(crate::modules::Delay<16>),
+    }
+
+    #[test]
+    fn modularize_should_forward_to_the_wrapped_type() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let osc = Oscillator::new(patchbay.point().unwrap());
+        let mut delay = crate::modules::Delay::new(patchbay.point().unwrap());
+        delay.set_input(osc.output());
+        let output = osc.output();
+
+        let mut processor: Processor<SAMPLE_RATE, 2, _> = Processor::new([
+            Some(RenamedModules::Delay(delay)),
+            Some(RenamedModules::Osc(osc)),
+        ]);
+
+        processor.process_modules(&mut patchbay);
+        processor.process_modules(&mut patchbay);
+
+        assert!(patchbay.get(output) > 0.0);
+
+        let names = [0, 1].map(|i| processor.get_module(i).map(|m| m.name()));
+
+        assert_eq!(names, [Some("Delay"), Some("Osc")]);
+    }
+
+    #[modularize]
+    enum RampModules {
+        Oscillator(Oscillator),
+        Constant(Constant),
+    }
+
+    #[test]
+    fn modularize_should_forward_ramps() {
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+        let constant = Constant {
+            value: 0.5,
+            output: patchbay.point().unwrap(),
+        };
+
+        osc.set_frequency(100.0);
+
+        let mut processor: Processor<SAMPLE_RATE, 2, _> = Processor::new([
+            Some(RampModules::Oscillator(osc)),
+            Some(RampModules::Constant(constant)),
+        ]);
+
+        for i in 0..2 {
+            let module = processor.get_module_mut(i).unwrap();
+            Module::<SAMPLE_RATE>::ramp(module, Oscillator::FREQUENCY, 200.0, 100);
+        }
+
+        for _ in 0..100 {
+            processor.advance_ramps();
+            processor.process_modules(&mut patchbay);
+        }
+
+        match processor.get_module(0) {
+            Some(RampModules::Oscillator(osc)) => assert_eq!(osc.get_frequency(), 200.0),
+            _ => panic!("expected `Oscillator` module type"),
+        }
+
+        // Modules without ramps ignore them
+        match processor.get_module(1) {
+            Some(RampModules::Constant(constant)) => assert_eq!(constant.value, 0.5),
+            _ => panic!("expected `Constant` module type"),
+        }
+    }
+
+    #[test]
+    fn modularize_should_forward_outputs() {
+        let mut patchbay: Patchbay<3> = Patchbay::new();
+
+        let constant = Constant {
+            value: 0.5,
+            output: patchbay.point().unwrap(),
+        };
+        let split = Split {
+            input: constant.output.signal(),
+            left: patchbay.point().unwrap(),
+            right: patchbay.point().unwrap(),
+        };
+
+        let mut processor: Processor<SAMPLE_RATE, 2, _> = Processor::new([
+            Some(MultiOutputModules::Split(split)),
+            Some(MultiOutputModules::Constant(constant)),
+        ]);
+
+        processor.process_modules(&mut patchbay);
+
+        let outputs = processor.get_module(0).unwrap().outputs();
+        assert_eq!(outputs.map(|s| patchbay.get(s)), [0.5, -0.5]);
+
+        let outputs = processor.get_module(1).unwrap().outputs();
+        assert_eq!(patchbay.get(outputs[0]), 0.5);
+        assert!(matches!(outputs[1], Signal::None));
     }
 }
@@ -0,0 +1,80 @@
+/// Identifies a parameter of a module for [`crate::Module::ramp`], modules list theirs as
+/// associated constants, e.g. [`crate::modules::Oscillator::FREQUENCY`].
+pub type ParamId = usize;
+
+/// State of a single linear parameter ramp, for implementing [`crate::Module::ramp`].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Ramp {
+    target: f32,
+    step: f32,
+    remaining: usize,
+}
+
+impl Ramp {
+    pub fn new() -> Self {
+        Ramp {
+            target: 0.0,
+            step: 0.0,
+            remaining: 0,
+        }
+    }
+
+    /// Start ramping from `value` to `target` over `samples` samples.
+    pub fn start(&mut self, value: f32, target: f32, samples: usize) {
+        self.target = target;
+        self.step = (target - value) / samples.max(1) as f32;
+        self.remaining = samples.max(1);
+    }
+
+    /// Move `value` one sample along the ramp, landing exactly on the target at the end.
+    pub fn advance(&mut self, value: &mut f32) {
+        if self.remaining == 0 {
+            return;
+        }
+
+        self.remaining -= 1;
+
+        *value = if self.remaining == 0 {
+            self.target
+        } else {
+            *value + self.step
+        };
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.remaining > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modules::Oscillator;
+    use crate::{Module, Patchbay, Processor};
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn ramp_should_reach_the_target_at_the_given_sample() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+
+        osc.set_frequency(100.0);
+        Module::<SAMPLE_RATE>::ramp(&mut osc, Oscillator::FREQUENCY, 200.0, 100);
+
+        let mut processor: Processor<SAMPLE_RATE, 1, Oscillator> = Processor::new([Some(osc)]);
+        let frequency = |processor: &Processor<SAMPLE_RATE, 1, Oscillator>| {
+            processor.get_module(0).unwrap().get_frequency()
+        };
+
+        for i in 1..=150 {
+            processor.advance_ramps();
+            processor.process_modules(&mut patchbay);
+
+            if i < 100 {
+                assert!((frequency(&processor) - (100.0 + i as f32)).abs() < 0.01);
+            } else {
+                assert_eq!(frequency(&processor), 200.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+/// Small xorshift pseudo random number generator, deterministic for a given seed.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Random {
+    state: u32,
+}
+
+impl Random {
+    pub fn new(seed: u32) -> Self {
+        // Xorshift gets stuck on a state of zero
+        let state = if seed == 0 { 0x9e37_79b9 } else { seed };
+
+        Random { state }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+
+        self.state = x;
+        x
+    }
+
+    /// Random value between 0.0 and 1.0.
+    pub fn next_f32(&mut self) -> f32 {
+        // Use the upper 24 bits which fit exactly in the mantissa
+        (self.next_u32() >> 8) as f32 / (1 << 24) as f32
+    }
+
+    /// Random value between -1.0 and 1.0.
+    pub fn next_bipolar(&mut self) -> f32 {
+        self.next_f32() * 2.0 - 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_should_be_deterministic_for_a_seed() {
+        let mut a = Random::new(42);
+        let mut b = Random::new(42);
+        let mut c = Random::new(43);
+
+        for _ in 0..100 {
+            let value = a.next_u32();
+            assert_eq!(value, b.next_u32());
+            assert_ne!(value, c.next_u32());
+        }
+    }
+
+    #[test]
+    fn random_should_stay_within_range() {
+        let mut random = Random::new(0);
+        let mut sum = 0.0;
+
+        for _ in 0..10_000 {
+            let value = random.next_bipolar();
+            assert!((-1.0..1.0).contains(&value));
+            sum += value;
+        }
+
+        // Roughly centered around zero
+        assert!((sum / 10_000.0f32).abs() < 0.05);
+    }
+}
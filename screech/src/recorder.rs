@@ -0,0 +1,130 @@
+use crate::{Patchbay, Signal};
+
+/// Captures the sample values of a [`Signal`] into a fixed size buffer.
+///
+/// Handy for tests and offline rendering where the output of a patch needs to be collected.
+///
+/// ```
+/// use screech::{Patchbay, Recorder, Signal};
+///
+/// let patchbay: Patchbay<8> = Patchbay::new();
+/// let mut recorder: Recorder<4> = Recorder::new(Signal::Fixed(0.5));
+///
+/// for _ in 0..6 {
+///     recorder.capture(&patchbay);
+/// }
+///
+/// assert_eq!(recorder.samples(), &[0.5; 4]);
+/// assert!(recorder.is_full());
+///
+/// let (buffer, length) = recorder.take();
+/// assert_eq!(length, 4);
+/// assert_eq!(buffer, [0.5; 4]);
+/// assert!(recorder.is_empty());
+/// ```
+pub struct Recorder<const SIZE: usize> {
+    input: Signal,
+    buffer: [f32; SIZE],
+    length: usize,
+}
+
+impl<const SIZE: usize> Recorder<SIZE> {
+    pub fn new(input: Signal) -> Self {
+        Recorder {
+            input,
+            buffer: [0.0; SIZE],
+            length: 0,
+        }
+    }
+
+    pub fn set_input(&mut self, input: Signal) -> &mut Self {
+        self.input = input;
+        self
+    }
+
+    /// Append the current sample value of the input, returns `false` if the buffer is full.
+    pub fn capture<const P: usize>(&mut self, patchbay: &Patchbay<P>) -> bool {
+        if self.length >= SIZE {
+            return false;
+        }
+
+        self.buffer[self.length] = patchbay.get(self.input);
+        self.length += 1;
+
+        true
+    }
+
+    /// The captured samples so far.
+    pub fn samples(&self) -> &[f32] {
+        &self.buffer[..self.length]
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.length >= SIZE
+    }
+
+    /// Take the buffer and the amount of captured samples in it, leaving the recorder empty.
+    pub fn take(&mut self) -> ([f32; SIZE], usize) {
+        let length = self.length;
+        self.length = 0;
+
+        (core::mem::replace(&mut self.buffer, [0.0; SIZE]), length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::Oscillator;
+    use crate::Processor;
+
+    #[test]
+    fn recorder_should_capture_samples() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut point = patchbay.point().unwrap();
+        let mut recorder: Recorder<16> = Recorder::new(point.signal());
+
+        patchbay.set(&mut point, 0.25);
+
+        for _ in 0..10 {
+            assert!(recorder.capture(&patchbay));
+        }
+
+        assert_eq!(recorder.len(), 10);
+        assert_eq!(recorder.samples(), &[0.25; 10]);
+
+        let (buffer, length) = recorder.take();
+
+        assert_eq!(length, 10);
+        assert_eq!(buffer[..10], [0.25; 10]);
+        assert_eq!(buffer[10..], [0.0; 6]);
+        assert!(recorder.is_empty());
+    }
+
+    #[test]
+    fn recorder_should_capture_processor_output() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut osc = Oscillator::new(patchbay.point().unwrap());
+        let mut recorder: Recorder<8> = Recorder::new(osc.output());
+
+        osc.output_saw();
+
+        let mut processor: Processor<48_000, 1, _> = Processor::new([Some(osc)]);
+
+        while !recorder.is_full() {
+            processor.process_modules(&mut patchbay);
+            recorder.capture(&patchbay);
+        }
+
+        assert!(!recorder.capture(&patchbay));
+        assert!(recorder.samples().windows(2).all(|w| w[1] > w[0]));
+    }
+}
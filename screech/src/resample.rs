@@ -0,0 +1,192 @@
+use crate::math;
+use core::f32::consts::PI;
+
+// Filter taps per phase of the polyphase filters
+const TAPS: usize = 16;
+
+/// Windowed sinc lowpass at the nyquist frequency of the lower rate, split into its phases.
+///
+/// Phase `p` holds taps `p`, `p + FACTOR`, `p + 2 * FACTOR` and so on of the full filter.
+fn polyphase_lowpass<const FACTOR: usize>() -> [[f32; TAPS]; FACTOR] {
+    let length = FACTOR * TAPS;
+    let center = (length - 1) as f32 * 0.5;
+    let cutoff = 0.5 / FACTOR as f32;
+    let mut phases = [[0.0; TAPS]; FACTOR];
+    let mut sum = 0.0;
+
+    for (p, phase) in phases.iter_mut().enumerate() {
+        for (k, tap) in phase.iter_mut().enumerate() {
+            let n = k * FACTOR + p;
+            let x = n as f32 - center;
+
+            let sinc = if x == 0.0 {
+                2.0 * cutoff
+            } else {
+                math::sin(2.0 * PI * cutoff * x) / (PI * x)
+            };
+
+            // Blackman window
+            let w = 2.0 * PI * n as f32 / (length - 1) as f32;
+            let window = 0.42 - 0.5 * math::cos(w) + 0.08 * math::cos(2.0 * w);
+
+            *tap = sinc * window;
+            sum += *tap;
+        }
+    }
+
+    // Unity gain at DC
+    for tap in phases.iter_mut().flatten() {
+        *tap /= sum;
+    }
+
+    phases
+}
+
+/// Raises the sample rate by `FACTOR` using a polyphase FIR anti-imaging filter.
+///
+/// Every input sample turns into `FACTOR` output samples, delayed by roughly
+/// `FACTOR * 8` samples at the higher rate.
+///
+/// ```
+/// use screech::Upsampler;
+///
+/// let mut upsampler: Upsampler<4> = Upsampler::new();
+/// let samples: [f32; 4] = upsampler.process(0.5);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Upsampler<const FACTOR: usize> {
+    phases: [[f32; TAPS]; FACTOR],
+    history: [f32; TAPS],
+}
+
+impl<const FACTOR: usize> Upsampler<FACTOR> {
+    pub fn new() -> Self {
+        Upsampler {
+            phases: polyphase_lowpass::<FACTOR>(),
+            history: [0.0; TAPS],
+        }
+    }
+
+    pub fn process(&mut self, input: f32) -> [f32; FACTOR] {
+        self.history.copy_within(0..TAPS - 1, 1);
+        self.history[0] = input;
+
+        let mut output = [0.0; FACTOR];
+
+        for (sample, phase) in output.iter_mut().zip(self.phases.iter()) {
+            let sum: f32 = phase
+                .iter()
+                .zip(self.history.iter())
+                .map(|(h, x)| h * x)
+                .sum();
+
+            // Make up for the energy lost to the stuffed zeros
+            *sample = sum * FACTOR as f32;
+        }
+
+        output
+    }
+}
+
+impl<const FACTOR: usize> Default for Upsampler<FACTOR> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lowers the sample rate by `FACTOR` using a polyphase FIR anti-aliasing filter.
+///
+/// Every `FACTOR` input samples, oldest first, turn into a single output sample, delayed by
+/// roughly `FACTOR * 8` samples at the higher rate.
+///
+/// ```
+/// use screech::Downsampler;
+///
+/// let mut downsampler: Downsampler<4> = Downsampler::new();
+/// let sample: f32 = downsampler.process([0.1, 0.2, 0.3, 0.4]);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Downsampler<const FACTOR: usize> {
+    phases: [[f32; TAPS]; FACTOR],
+    history: [[f32; TAPS]; FACTOR],
+}
+
+impl<const FACTOR: usize> Downsampler<FACTOR> {
+    pub fn new() -> Self {
+        Downsampler {
+            phases: polyphase_lowpass::<FACTOR>(),
+            history: [[0.0; TAPS]; FACTOR],
+        }
+    }
+
+    pub fn process(&mut self, input: [f32; FACTOR]) -> f32 {
+        let mut output = 0.0;
+
+        // Phase `p` filters every input sample `p` samples before the newest one
+        for (p, (phase, history)) in self.phases.iter().zip(self.history.iter_mut()).enumerate() {
+            history.copy_within(0..TAPS - 1, 1);
+            history[0] = input[FACTOR - 1 - p];
+
+            output += phase
+                .iter()
+                .zip(history.iter())
+                .map(|(h, x)| h * x)
+                .sum::<f32>();
+        }
+
+        output
+    }
+}
+
+impl<const FACTOR: usize> Default for Downsampler<FACTOR> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::f32::consts::TAU;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    fn rms(samples: &[f32]) -> f32 {
+        math::sqrt(samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32)
+    }
+
+    #[test]
+    fn resampling_should_round_trip_a_sine() {
+        let mut upsampler: Upsampler<4> = Upsampler::new();
+        let mut downsampler: Downsampler<4> = Downsampler::new();
+        let mut input = [0.0; 4800];
+        let mut output = [0.0; 4800];
+
+        for i in 0..4800 {
+            input[i] = math::sin(TAU * 1000.0 * i as f32 / SAMPLE_RATE as f32);
+            output[i] = downsampler.process(upsampler.process(input[i]));
+        }
+
+        // Skip the filters filling up
+        let gain = rms(&output[480..]) / rms(&input[480..]);
+        assert!((gain - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn downsampling_should_attenuate_out_of_band_content() {
+        let mut downsampler: Downsampler<4> = Downsampler::new();
+        let mut output = [0.0; 4800];
+
+        // 40kHz at four times the sample rate, above the nyquist frequency of 24kHz
+        for (i, sample) in output.iter_mut().enumerate() {
+            let block = core::array::from_fn(|j| {
+                let n = i * 4 + j;
+                math::sin(TAU * 40_000.0 * n as f32 / (SAMPLE_RATE * 4) as f32)
+            });
+
+            *sample = downsampler.process(block);
+        }
+
+        assert!(rms(&output[480..]) < 0.01);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{Patchbay, Signal};
+
+/// Oscilloscope style capture of a [`Signal`], triggered on a rising zero crossing.
+///
+/// Capturing starts at the first sample at or above zero after a negative sample, so every
+/// frame of a periodic signal starts at the same point in its cycle. Once `SIZE` samples have
+/// been captured the frame is made available through [`Scope::frame`] and the scope waits for
+/// the next trigger.
+///
+/// ```
+/// use screech::{Patchbay, Scope};
+///
+/// let mut patchbay: Patchbay<1> = Patchbay::new();
+/// let mut point = patchbay.point().unwrap();
+/// let mut scope: Scope<2> = Scope::new(point.signal());
+///
+/// for sample in [-0.5, 0.25, 0.5, 1.0] {
+///     patchbay.set(&mut point, sample);
+///     scope.capture(&patchbay);
+/// }
+///
+/// assert_eq!(scope.frame(), &[0.25, 0.5]);
+/// ```
+pub struct Scope<const SIZE: usize> {
+    input: Signal,
+    buffer: [f32; SIZE],
+    frame: [f32; SIZE],
+    length: usize,
+    has_frame: bool,
+    triggered: bool,
+    previous: f32,
+}
+
+impl<const SIZE: usize> Scope<SIZE> {
+    pub fn new(input: Signal) -> Self {
+        Scope {
+            input,
+            buffer: [0.0; SIZE],
+            frame: [0.0; SIZE],
+            length: 0,
+            has_frame: false,
+            triggered: false,
+            previous: 0.0,
+        }
+    }
+
+    pub fn set_input(&mut self, input: Signal) -> &mut Self {
+        self.input = input;
+        self
+    }
+
+    /// Capture the current sample value of the input, returns `true` when a new frame has been
+    /// completed.
+    pub fn capture<const P: usize>(&mut self, patchbay: &Patchbay<P>) -> bool {
+        let sample = patchbay.get(self.input);
+
+        if !self.triggered && self.previous < 0.0 && sample >= 0.0 {
+            self.triggered = true;
+            self.length = 0;
+        }
+
+        self.previous = sample;
+
+        if !self.triggered || SIZE == 0 {
+            return false;
+        }
+
+        self.buffer[self.length] = sample;
+        self.length += 1;
+
+        if self.length < SIZE {
+            return false;
+        }
+
+        self.frame = self.buffer;
+        self.has_frame = true;
+        self.triggered = false;
+
+        true
+    }
+
+    /// The last completed frame, empty until the first frame has been captured.
+    pub fn frame(&self) -> &[f32] {
+        if self.has_frame {
+            &self.frame
+        } else {
+            &[]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math;
+    use core::f32::consts::TAU;
+
+    #[test]
+    fn scope_should_start_frames_at_a_rising_zero_crossing() {
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut point = patchbay.point().unwrap();
+        let mut scope: Scope<64> = Scope::new(point.signal());
+        let mut frames = 0;
+
+        // 480 samples per cycle, starting at an arbitrary phase
+        for i in 0..4800 {
+            let sample = math::sin(TAU * (i as f32 / 480.0 + 0.3));
+            patchbay.set(&mut point, sample);
+
+            if scope.capture(&patchbay) {
+                frames += 1;
+
+                let frame = scope.frame();
+                let step = TAU / 480.0;
+
+                assert_eq!(frame.len(), 64);
+                assert!(frame[0] >= 0.0 && frame[0] < step);
+                assert!(frame[1] > frame[0]);
+            }
+        }
+
+        // Triggered once every cycle
+        assert_eq!(frames, 10);
+    }
+
+    #[test]
+    fn scope_should_wait_for_a_trigger() {
+        let patchbay: Patchbay<1> = Patchbay::new();
+        let mut scope: Scope<4> = Scope::new(Signal::Fixed(0.5));
+
+        for _ in 0..16 {
+            assert!(!scope.capture(&patchbay));
+        }
+
+        assert!(scope.frame().is_empty());
+    }
+}
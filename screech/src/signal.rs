@@ -23,8 +23,10 @@
 /// ```
 #[derive(Copy, Clone)]
 pub enum Signal {
-    /// Refers to a sample set by another source
-    PatchPoint(usize),
+    /// Refers to a sample set by another source, identified by the index of the point and the
+    /// generation of the point at the time the signal was taken. Signals of a point that has
+    /// since been released read as silence.
+    PatchPoint(usize, u32),
     /// Fixed sample value, useful for ad-hoc settings or independent values.
     Fixed(f32),
     /// No signal, for example an input with nothing connected usually references ground.
@@ -0,0 +1,188 @@
+use crate::{Module, PatchPoint, Patchbay, Processor, Signal};
+
+/// Runs a self-contained sub-patch at its own sample rate within a host patch.
+///
+/// The inner [`Processor`] runs at `RATE` with its own [`Patchbay`], one of its signals is
+/// linearly interpolated to the host sample rate and set on the output. Running control signals
+/// like LFOs at a fraction of the host rate saves processing, faster rates are possible as
+/// well in which case the inner processor runs multiple times per host sample.
+///
+/// ```
+/// use screech::{Patchbay, Processor, SubProcessor};
+/// use screech::modules::Oscillator;
+///
+/// let mut inner: Patchbay<1> = Patchbay::new();
+/// let mut lfo = Oscillator::new(inner.point().unwrap());
+/// let source = lfo.output();
+///
+/// lfo.set_frequency(2.0);
+///
+/// // Run the LFO at 6kHz
+/// let processor: Processor<6_000, 1, Oscillator> = Processor::new([Some(lfo)]);
+///
+/// let mut patchbay: Patchbay<1> = Patchbay::new();
+/// let sub = SubProcessor::new(processor, inner, source, patchbay.point().unwrap());
+/// ```
+pub struct SubProcessor<const RATE: usize, const MODULES: usize, const POINTS: usize, M>
+where
+    M: Module<RATE>,
+{
+    processor: Processor<RATE, MODULES, M>,
+    patchbay: Patchbay<POINTS>,
+    source: Signal,
+    output: PatchPoint,
+    phase: f32,
+    previous: f32,
+    current: f32,
+}
+
+impl<const RATE: usize, const MODULES: usize, const POINTS: usize, M>
+    SubProcessor<RATE, MODULES, POINTS, M>
+where
+    M: Module<RATE>,
+{
+    /// Wrap a processor and its patchbay, `source` is the signal within the inner patchbay
+    /// that gets resampled to the output.
+    pub fn new(
+        processor: Processor<RATE, MODULES, M>,
+        patchbay: Patchbay<POINTS>,
+        source: Signal,
+        output: PatchPoint,
+    ) -> Self {
+        SubProcessor {
+            processor,
+            patchbay,
+            source,
+            output,
+            phase: 0.0,
+            previous: 0.0,
+            current: 0.0,
+        }
+    }
+
+    pub fn output(&self) -> Signal {
+        self.output.signal()
+    }
+
+    pub fn set_source(&mut self, source: Signal) -> &mut Self {
+        self.source = source;
+        self
+    }
+
+    pub fn processor(&self) -> &Processor<RATE, MODULES, M> {
+        &self.processor
+    }
+
+    pub fn processor_mut(&mut self) -> &mut Processor<RATE, MODULES, M> {
+        &mut self.processor
+    }
+}
+
+impl<const SAMPLE_RATE: usize, const RATE: usize, const MODULES: usize, const POINTS: usize, M>
+    Module<SAMPLE_RATE> for SubProcessor<RATE, MODULES, POINTS, M>
+where
+    M: Module<RATE>,
+{
+    fn process<const P: usize>(&mut self, patchbay: &mut Patchbay<P>) {
+        self.phase += RATE as f32 / SAMPLE_RATE as f32;
+
+        while self.phase >= 1.0 {
+            self.phase -= 1.0;
+            self.processor.process_modules(&mut self.patchbay);
+            self.previous = self.current;
+            self.current = self.patchbay.get(self.source);
+        }
+
+        let output = self.previous + (self.current - self.previous) * self.phase;
+
+        patchbay.set(&mut self.output, output);
+    }
+
+    /// Interpolating between inner samples delays the output by one inner sample.
+    fn latency(&self) -> usize {
+        (SAMPLE_RATE / RATE.max(1)).max(1)
+    }
+
+    fn reset<const P: usize>(&mut self, _patchbay: &mut Patchbay<P>) {
+        self.patchbay.reset();
+        self.processor.reset_modules(&mut self.patchbay);
+        self.phase = 0.0;
+        self.previous = 0.0;
+        self.current = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::Oscillator;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    #[test]
+    fn sub_processor_should_resample_a_slower_sub_patch() {
+        let mut inner: Patchbay<1> = Patchbay::new();
+        let mut lfo = Oscillator::new(inner.point().unwrap());
+        let source = lfo.output();
+
+        lfo.set_frequency(2.0);
+
+        let processor: Processor<{ SAMPLE_RATE / 8 }, 1, Oscillator> = Processor::new([Some(lfo)]);
+
+        let mut patchbay: Patchbay<2> = Patchbay::new();
+        let mut sub = SubProcessor::new(processor, inner, source, patchbay.point().unwrap());
+        let mut reference = Oscillator::new(patchbay.point().unwrap());
+
+        reference.set_frequency(2.0);
+
+        let latency = Module::<SAMPLE_RATE>::latency(&sub);
+        assert_eq!(latency, 8);
+
+        let mut expected = [0.0; SAMPLE_RATE / 2];
+        let mut output = [0.0; SAMPLE_RATE / 2];
+
+        for i in 0..SAMPLE_RATE / 2 {
+            Module::<SAMPLE_RATE>::process(&mut sub, &mut patchbay);
+            Module::<SAMPLE_RATE>::process(&mut reference, &mut patchbay);
+
+            output[i] = patchbay.get(sub.output());
+            expected[i] = patchbay.get(reference.output());
+        }
+
+        for i in latency..SAMPLE_RATE / 2 {
+            assert!((output[i] - expected[i - latency]).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn sub_processor_should_reset_the_sub_patch() {
+        let mut inner: Patchbay<1> = Patchbay::new();
+        let mut lfo = Oscillator::new(inner.point().unwrap());
+        let source = lfo.output();
+
+        lfo.set_frequency(2.0);
+
+        let processor: Processor<{ SAMPLE_RATE / 8 }, 1, Oscillator> = Processor::new([Some(lfo)]);
+
+        let mut patchbay: Patchbay<1> = Patchbay::new();
+        let mut sub = SubProcessor::new(processor, inner, source, patchbay.point().unwrap());
+
+        let mut first = [0.0; 100];
+
+        for value in first.iter_mut() {
+            Module::<SAMPLE_RATE>::process(&mut sub, &mut patchbay);
+            *value = patchbay.get(sub.output());
+        }
+
+        for _ in 0..1000 {
+            Module::<SAMPLE_RATE>::process(&mut sub, &mut patchbay);
+        }
+
+        Module::<SAMPLE_RATE>::reset(&mut sub, &mut patchbay);
+
+        for value in first {
+            Module::<SAMPLE_RATE>::process(&mut sub, &mut patchbay);
+            assert_eq!(patchbay.get(sub.output()), value);
+        }
+    }
+}
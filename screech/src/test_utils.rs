@@ -0,0 +1,32 @@
+use crate::{Module, Patchbay, Processor, Signal};
+
+// Difference allowed between an expected and processed value
+const TOLERANCE: f32 = 1e-6;
+
+/// Run the processor once for every expected value and assert the output matches each of
+/// them within a small tolerance.
+pub(crate) fn assert_output_sequence<
+    const SAMPLE_RATE: usize,
+    const MODULES: usize,
+    const POINTS: usize,
+    M: Module<SAMPLE_RATE>,
+>(
+    processor: &mut Processor<SAMPLE_RATE, MODULES, M>,
+    patchbay: &mut Patchbay<POINTS>,
+    output: Signal,
+    expected: &[f32],
+) {
+    for (i, expected_value) in expected.iter().enumerate() {
+        processor.process_modules(patchbay);
+        let value = patchbay.get(output);
+
+        assert!(
+            (value - expected_value).abs() <= TOLERANCE,
+            "output differs at sample {}\n  expected: {}\n    actual: {}\n  sequence: {:?}",
+            i,
+            expected_value,
+            value,
+            expected,
+        );
+    }
+}